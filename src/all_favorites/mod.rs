@@ -1,6 +1,10 @@
-use std::path::Path;
+pub use sort_by::SortBy;
+mod sort_by;
+
+use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 use crate::{favorite_folder_record::FavoriteFolderPath, trimmed_not_empty_text::NonEmptyText};
 
@@ -20,6 +24,14 @@ pub enum AfterInsertion {
     Added,
 }
 
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum RenameError {
+    #[error("No favorite with name {0} to be renamed")]
+    NotFound(NonEmptyText),
+    #[error("Name {0} is already used by another location. Use \"--force\" to replace it")]
+    NameTaken(NonEmptyText),
+}
+
 impl AllFavorites {
     pub fn new(folders: Vec<FavoriteFolderPath>) -> Self {
         Self(folders)
@@ -36,7 +48,9 @@ impl AllFavorites {
     pub fn insert(&mut self, new_favorite: FavoriteFolderPath) -> AfterInsertion {
         match self.find_by_name_index(new_favorite.get_name()) {
             Some(index) => {
+                let mut new_favorite = new_favorite;
                 let to_set = self.0.get_mut(index).unwrap();
+                new_favorite.keep_usage_of(to_set);
                 *to_set = new_favorite;
                 AfterInsertion::Changed
             }
@@ -51,6 +65,20 @@ impl AllFavorites {
         self.find_by_name(&name)
     }
 
+    /// Resolves text like "<name>/<relative/path>".
+    /// The first segment is looked up as name and the remainder is joined onto its location.
+    /// Returns none if there is no relative path after the name or the name is not found.
+    pub fn get_with_sub_path(
+        &self,
+        name_with_sub_path: &str,
+    ) -> Option<(&FavoriteFolderPath, PathBuf)> {
+        let (name, sub_path) = name_with_sub_path.split_once('/')?;
+        let name = NonEmptyText::new(name).ok()?;
+        let found = self.get(name)?;
+        let joined = found.path().join(sub_path.trim_start_matches('/'));
+        Some((found, joined))
+    }
+
     pub fn filtered_containing_name(self, name: NonEmptyText) -> AllFavorites {
         let filtered = self
             .0
@@ -60,29 +88,122 @@ impl AllFavorites {
         Self::new(filtered)
     }
 
-    pub fn rename(&mut self, old_name: &NonEmptyText, new_name: NonEmptyText) -> bool {
-        match self.find_by_name_mut(old_name) {
-            Some(to_rename) => {
-                to_rename.set_name(new_name);
+    pub fn filtered_with_tag(self, tag: &str) -> AllFavorites {
+        let filtered = self
+            .0
+            .into_iter()
+            .filter(|possible_match| possible_match.has_tag(tag))
+            .collect();
+        Self::new(filtered)
+    }
+
+    /// Adds the tags to the favorite with the name or removes them if `remove` is true.
+    /// Returns false if no favorite with this name exists.
+    pub fn change_tags(&mut self, name: &str, tags: &[NonEmptyText], remove: bool) -> bool {
+        match self.find_by_name_mut(name) {
+            Some(to_tag) if remove => {
+                to_tag.remove_tags(tags);
+                true
+            }
+            Some(to_tag) => {
+                to_tag.add_tags(tags);
                 true
             }
             None => false,
         }
     }
 
-    pub fn remove_with_name(&mut self, name: &NonEmptyText) -> bool {
-        match self.find_by_name_index(name) {
-            Some(index) => {
-                self.0.remove(index);
+    /// Sorts all favorites in a stable way. The order is flipped if `reverse` is true.
+    /// If `sort_by` is none, then only the order of insertion is flipped if `reverse` is true.
+    pub fn sort(&mut self, sort_by: Option<SortBy>, reverse: bool) {
+        if let Some(sort_by) = sort_by {
+            sort_by.sort(&mut self.0);
+        }
+        if reverse {
+            self.0.reverse();
+        }
+    }
+
+    /// Remembers that the location under the name was used at the time `now`.
+    /// Returns false if no favorite with this name exists.
+    pub fn mark_as_used(&mut self, name: &str, now: u64) -> bool {
+        match self.find_by_name_mut(name) {
+            Some(to_mark) => {
+                to_mark.mark_as_used(now);
                 true
             }
             None => false,
         }
     }
 
-    pub fn clean_all_dangling(&mut self, mut on_is_dangling_check: impl FnMut(&Path) -> bool) {
+    /// Fails if the old name does not exist or the new name is used by another favorite.
+    /// With `overwrite` all other favorites under the old or new name are removed instead.
+    /// This way the first of several favorites under the same name is kept by renaming it
+    /// to its own name.
+    pub fn rename(
+        &mut self,
+        old_name: &NonEmptyText,
+        new_name: NonEmptyText,
+        overwrite: bool,
+    ) -> Result<(), RenameError> {
+        let Some(index) = self.find_by_name_index(old_name) else {
+            return Err(RenameError::NotFound(old_name.clone()));
+        };
+        if !overwrite {
+            if old_name == &new_name {
+                return Ok(());
+            }
+            if self.find_by_name_index(&new_name).is_some() {
+                return Err(RenameError::NameTaken(new_name));
+            }
+            self.0[index].set_name(new_name);
+            return Ok(());
+        }
+
+        let mut position = 0;
+        self.0.retain(|favorite| {
+            let name = favorite.get_name();
+            let keep =
+                position == index || (name != old_name.as_str() && name != new_name.as_str());
+            position += 1;
+            keep
+        });
+        let index = self.find_by_name_index(old_name).unwrap();
+        self.0[index].set_name(new_name);
+        Ok(())
+    }
+
+    /// First name which is used by more than one favorite.
+    /// Such a name could only ever address the first of them.
+    pub fn find_duplicate_name(&self) -> Option<&str> {
+        self.0.iter().enumerate().find_map(|(index, favorite)| {
+            let name = favorite.get_name();
+            self.0[..index]
+                .iter()
+                .any(|earlier| earlier.get_name() == name)
+                .then_some(name)
+        })
+    }
+
+    /// Removes every favorite under the name, even several ones.
+    /// Returns false if no favorite with this name exists.
+    pub fn remove_with_name(&mut self, name: &NonEmptyText) -> bool {
+        let before = self.0.len();
         self.0
-            .retain_mut(|maybe_dangling| !on_is_dangling_check(maybe_dangling.path()));
+            .retain(|favorite| favorite.get_name() != name.as_str());
+        self.0.len() != before
+    }
+
+    /// Returns the removed favorites.
+    pub fn clean_all_dangling(
+        &mut self,
+        mut on_is_dangling_check: impl FnMut(&Path) -> bool,
+    ) -> Vec<FavoriteFolderPath> {
+        let (dangling, kept) = std::mem::take(&mut self.0)
+            .into_iter()
+            .partition(|maybe_dangling| on_is_dangling_check(&maybe_dangling.path()));
+        self.0 = kept;
+        dangling
     }
 
     fn find_by_name_mut(&mut self, name: &str) -> Option<&mut FavoriteFolderPath> {
@@ -113,11 +234,15 @@ impl From<AllFavorites> for Vec<FavoriteFolderPath> {
 #[cfg(test)]
 mod testing {
 
+    use std::path::PathBuf;
+
     use crate::{
         all_favorites::AfterInsertion, favorite_folder_record::FavoriteFolderPath,
-        trimmed_not_empty_text::NonEmptyText, AllFavorites,
+        favorite_location::FavoriteLocation, trimmed_not_empty_text::NonEmptyText, AllFavorites,
     };
 
+    use super::{RenameError, SortBy};
+
     const INPUT: &str = include_str!("test_input.json");
     const INPUT_LONGER: &str = include_str!("longer_test_input.json");
     fn given_initial() -> AllFavorites {
@@ -134,15 +259,52 @@ mod testing {
     fn if_dangling_delete_a_path() {
         let expected = AllFavorites::from_iter([FavoriteFolderPath::new(
             NonEmptyText::unwrap("proc_macro"),
-            NonEmptyText::unwrap("/home/some_user/Code/rust/proc-macro-workshop"),
+            FavoriteLocation::unwrap("/home/some_user/Code/rust/proc-macro-workshop"),
         )]);
 
         let mut given = given_initial();
-        given.clean_all_dangling(|path| {
+        let removed = given.clean_all_dangling(|path| {
             let path = path.to_str().unwrap();
             path == "/home/some_user/Documents/Studium" || path == "/home/some_user/Code/rust"
         });
         assert_eq!(expected, given);
+        assert_eq!(
+            vec!["studium", "dev_rust"],
+            names_of(&AllFavorites::new(removed))
+        );
+    }
+
+    #[test]
+    fn expand_location_before_checking_for_dangling() {
+        let expected = AllFavorites::from_iter([
+            FavoriteFolderPath::new(NonEmptyText::unwrap("home"), FavoriteLocation::unwrap("~")),
+            FavoriteFolderPath::new(
+                NonEmptyText::unwrap("home_var"),
+                FavoriteLocation::unwrap("${HOME}"),
+            ),
+            FavoriteFolderPath::new(
+                NonEmptyText::unwrap("default_var"),
+                FavoriteLocation::unwrap("${FAV_FOLDER_NOT_SET:-/}"),
+            ),
+        ]);
+
+        let mut given = AllFavorites::from_iter([
+            FavoriteFolderPath::new(NonEmptyText::unwrap("home"), FavoriteLocation::unwrap("~")),
+            FavoriteFolderPath::new(
+                NonEmptyText::unwrap("home_var"),
+                FavoriteLocation::unwrap("${HOME}"),
+            ),
+            FavoriteFolderPath::new(
+                NonEmptyText::unwrap("default_var"),
+                FavoriteLocation::unwrap("${FAV_FOLDER_NOT_SET:-/}"),
+            ),
+            FavoriteFolderPath::new(
+                NonEmptyText::unwrap("not_there"),
+                FavoriteLocation::unwrap("~/fav_folder_not_there/at_all"),
+            ),
+        ]);
+        given.clean_all_dangling(|path| !path.exists());
+        assert_eq!(expected, given);
     }
 
     #[test]
@@ -157,6 +319,26 @@ mod testing {
         assert_case("dev_rust", Some(1));
     }
 
+    #[test]
+    fn resolve_name_with_sub_path() {
+        fn assert_case(given: &str, expected: Option<&str>) {
+            let data = given_initial();
+            let actual = data.get_with_sub_path(given).map(|(_, joined)| joined);
+            let expected = expected.map(PathBuf::from);
+            assert_eq!(expected, actual, "Given: {}", given);
+        }
+
+        assert_case(
+            "dev_rust/src/main.rs",
+            Some("/home/some_user/Code/rust/src/main.rs"),
+        );
+        assert_case("dev_rust//src", Some("/home/some_user/Code/rust/src"));
+        assert_case("dev_rust/", Some("/home/some_user/Code/rust/"));
+        assert_case("dev_rust", None);
+        assert_case("not_there/src", None);
+        assert_case("/src", None);
+    }
+
     #[test]
     fn name_not_found_dont_remove_it() {
         let (actual, has_deleted) = set_up_and_act_remove(NonEmptyText::unwrap("not_there"));
@@ -175,24 +357,113 @@ mod testing {
 
     #[test]
     fn name_found_rename_it() {
-        let (actual, has_deleted) = set_up_and_act_rename(
+        let (actual, outcome) = set_up_and_act_rename(
             NonEmptyText::unwrap("dev_rust"),
             NonEmptyText::unwrap("other_dev_rust"),
+            false,
         );
-        const EXPECTED: bool = true;
-        assert_eq!(EXPECTED, has_deleted);
+        assert_eq!(Ok(()), outcome);
         insta::assert_debug_snapshot!(actual);
     }
 
     #[test]
     fn no_name_found_dont_rename() {
-        let (actual, has_deleted) = set_up_and_act_rename(
+        let (actual, outcome) = set_up_and_act_rename(
             NonEmptyText::unwrap("not_found"),
             NonEmptyText::unwrap("is_an_error"),
+            true,
         );
-        const EXPECTED: bool = false;
-        assert_eq!(EXPECTED, has_deleted);
-        insta::assert_debug_snapshot!(actual);
+        assert_eq!(
+            Err(RenameError::NotFound(NonEmptyText::unwrap("not_found"))),
+            outcome
+        );
+        assert_eq!(given_initial(), actual);
+    }
+
+    #[test]
+    fn refuse_rename_onto_used_name_unless_overwritten() {
+        let (actual, outcome) = set_up_and_act_rename(
+            NonEmptyText::unwrap("proc_macro"),
+            NonEmptyText::unwrap("studium"),
+            false,
+        );
+        assert_eq!(
+            Err(RenameError::NameTaken(NonEmptyText::unwrap("studium"))),
+            outcome
+        );
+        assert_eq!(given_initial(), actual);
+
+        let (actual, outcome) = set_up_and_act_rename(
+            NonEmptyText::unwrap("proc_macro"),
+            NonEmptyText::unwrap("studium"),
+            true,
+        );
+        assert_eq!(Ok(()), outcome);
+        assert_eq!(vec!["dev_rust", "studium"], names_of(&actual));
+        assert_eq!(
+            "/home/some_user/Code/rust/proc-macro-workshop",
+            actual
+                .get(NonEmptyText::unwrap("studium"))
+                .unwrap()
+                .path()
+                .to_str()
+                .unwrap()
+        );
+        assert_eq!(None, actual.find_duplicate_name());
+    }
+
+    #[test]
+    fn repair_names_used_several_times() {
+        fn given_duplicated() -> AllFavorites {
+            let mut given = given_initial();
+            given.0.push(FavoriteFolderPath::new(
+                NonEmptyText::unwrap("dev_rust"),
+                FavoriteLocation::unwrap("/tmp"),
+            ));
+            given
+        }
+
+        let mut actual = given_duplicated();
+        assert!(actual.remove_with_name(&NonEmptyText::unwrap("dev_rust")));
+        assert_eq!(vec!["studium", "proc_macro"], names_of(&actual));
+
+        let mut actual = given_duplicated();
+        assert_eq!(
+            Ok(()),
+            actual.rename(
+                &NonEmptyText::unwrap("dev_rust"),
+                NonEmptyText::unwrap("dev_rust"),
+                true
+            )
+        );
+        assert_eq!(None, actual.find_duplicate_name());
+        assert_eq!(
+            given_initial().get(NonEmptyText::unwrap("dev_rust")),
+            actual.get(NonEmptyText::unwrap("dev_rust"))
+        );
+
+        let mut actual = given_duplicated();
+        assert_eq!(
+            Ok(()),
+            actual.rename(
+                &NonEmptyText::unwrap("dev_rust"),
+                NonEmptyText::unwrap("rust"),
+                true
+            )
+        );
+        assert_eq!(None, actual.find_duplicate_name());
+        assert_eq!(None, actual.get(NonEmptyText::unwrap("dev_rust")));
+    }
+
+    #[test]
+    fn detect_names_used_several_times() {
+        assert_eq!(None, given_initial().find_duplicate_name());
+        let mut given = given_initial();
+        given.0.push(FavoriteFolderPath::new(
+            NonEmptyText::unwrap("dev_rust"),
+            FavoriteLocation::unwrap("/tmp"),
+        ));
+        assert_eq!(Some("dev_rust"), given.find_duplicate_name());
     }
 
     #[test]
@@ -200,7 +471,7 @@ mod testing {
         let mut given_data = given_initial();
         let given = FavoriteFolderPath::new(
             NonEmptyText::unwrap("new_added"),
-            NonEmptyText::unwrap("~/some_data"),
+            FavoriteLocation::unwrap("~/some_data"),
         );
 
         const EXPECTED: AfterInsertion = AfterInsertion::Added;
@@ -214,7 +485,7 @@ mod testing {
         let mut given_data = given_initial();
         let given = FavoriteFolderPath::new(
             NonEmptyText::unwrap("dev_rust"),
-            NonEmptyText::unwrap("~/new_some_data"),
+            FavoriteLocation::unwrap("~/new_some_data"),
         );
 
         const EXPECTED: AfterInsertion = AfterInsertion::Changed;
@@ -239,12 +510,97 @@ mod testing {
         assert!(actual.as_slice().is_empty());
     }
 
+    #[test]
+    fn sort_by_name_naturally() {
+        let mut given = AllFavorites::from_iter(["dir10", "Dir2", "api", "dir1"].map(|name| {
+            FavoriteFolderPath::new(
+                NonEmptyText::unwrap(name),
+                FavoriteLocation::unwrap("/home/some_user"),
+            )
+        }));
+
+        given.sort(Some(SortBy::Name), false);
+        assert_eq!(vec!["api", "dir1", "Dir2", "dir10"], names_of(&given));
+
+        given.sort(Some(SortBy::Name), true);
+        assert_eq!(vec!["dir10", "Dir2", "dir1", "api"], names_of(&given));
+    }
+
+    #[test]
+    fn sort_by_usage_in_stable_way() {
+        let mut given = given_longer_initial();
+        assert!(given.mark_as_used("looping", 20));
+        assert!(given.mark_as_used("looping", 30));
+        assert!(given.mark_as_used("dev_rust", 10));
+        assert!(!given.mark_as_used("not_there", 10));
+
+        given.sort(Some(SortBy::UseCount), false);
+        assert_eq!(
+            vec!["studium", "developing", "proc_macro", "dev_rust", "looping"],
+            names_of(&given)
+        );
+
+        given.sort(Some(SortBy::LastUsed), true);
+        assert_eq!(
+            vec!["looping", "dev_rust", "proc_macro", "developing", "studium"],
+            names_of(&given)
+        );
+    }
+
+    #[test]
+    fn keep_usage_when_changing_location() {
+        let mut given_data = given_initial();
+        given_data.mark_as_used("dev_rust", 10);
+        let given = FavoriteFolderPath::new(
+            NonEmptyText::unwrap("dev_rust"),
+            FavoriteLocation::unwrap("~/new_some_data"),
+        )
+        .with_created(20);
+
+        given_data.insert(given);
+        let actual = given_data.get(NonEmptyText::unwrap("dev_rust")).unwrap();
+        assert_eq!(Some(10), actual.last_used());
+        assert_eq!(1, actual.use_count());
+        assert_eq!(Some(20), actual.created());
+    }
+
+    #[test]
+    fn select_by_added_and_removed_tags() {
+        let mut given = given_initial();
+        let (work, rust) = (NonEmptyText::unwrap("work"), NonEmptyText::unwrap("rust"));
+        assert!(given.change_tags("dev_rust", &[rust.clone(), work.clone()], false));
+        assert!(given.change_tags("proc_macro", std::slice::from_ref(&rust), false));
+        assert!(given.change_tags("proc_macro", std::slice::from_ref(&rust), false));
+        assert!(given.change_tags("studium", std::slice::from_ref(&work), false));
+        assert!(given.change_tags("studium", &[work], true));
+        assert!(!given.change_tags("not_there", &[rust], false));
+
+        assert_eq!(
+            &[NonEmptyText::unwrap("rust"), NonEmptyText::unwrap("work")],
+            given.get(NonEmptyText::unwrap("dev_rust")).unwrap().tags()
+        );
+        assert!(given
+            .get(NonEmptyText::unwrap("studium"))
+            .unwrap()
+            .tags()
+            .is_empty());
+        assert_eq!(
+            vec!["dev_rust", "proc_macro"],
+            names_of(&given.filtered_with_tag("rust"))
+        );
+    }
+
+    fn names_of(favorites: &AllFavorites) -> Vec<&str> {
+        favorites.iter().map(FavoriteFolderPath::get_name).collect()
+    }
+
     fn set_up_and_act_rename(
         old_name: NonEmptyText,
         new_name: NonEmptyText,
-    ) -> (AllFavorites, bool) {
+        overwrite: bool,
+    ) -> (AllFavorites, Result<(), RenameError>) {
         let mut given = given_initial();
-        let actual = given.rename(&old_name, new_name);
+        let actual = given.rename(&old_name, new_name, overwrite);
         (given, actual)
     }
 
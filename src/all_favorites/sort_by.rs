@@ -0,0 +1,120 @@
+use std::cmp::Ordering;
+
+use clap::ValueEnum;
+use serde::Deserialize;
+
+use crate::favorite_folder_record::FavoriteFolderPath;
+
+/// Order in which favorites are listed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SortBy {
+    /// By name where numbers within names are compared by their value like "dir2" before "dir10".
+    Name,
+    /// By location as saved.
+    Path,
+    /// Oldest first. Favorites created by older versions of this app come first.
+    Created,
+    /// Least recently used first. Never used favorites come first.
+    LastUsed,
+    /// Least often used first.
+    UseCount,
+    /// Existing locations first.
+    Exists,
+}
+
+impl SortBy {
+    /// Sorts in a stable way so favorites with equal keys keep their order.
+    pub fn sort(self, favorites: &mut [FavoriteFolderPath]) {
+        match self {
+            SortBy::Name => {
+                favorites.sort_by(|left, right| natural_cmp(left.get_name(), right.get_name()))
+            }
+            SortBy::Path => favorites.sort_by(|left, right| {
+                left.location()
+                    .as_os_str()
+                    .cmp(right.location().as_os_str())
+            }),
+            SortBy::Created => favorites.sort_by_key(FavoriteFolderPath::created),
+            SortBy::LastUsed => favorites.sort_by_key(FavoriteFolderPath::last_used),
+            SortBy::UseCount => favorites.sort_by_key(FavoriteFolderPath::use_count),
+            SortBy::Exists => favorites.sort_by_cached_key(|favorite| !favorite.does_exit()),
+        }
+    }
+}
+
+/// Compares texts case insensitive while numbers within them are compared by their value.
+/// Texts which only differ in case are compared case sensitive as the last resort.
+pub fn natural_cmp(left: &str, right: &str) -> Ordering {
+    let mut left_chunks = Chunks(left);
+    let mut right_chunks = Chunks(right);
+    loop {
+        let ordering = match (left_chunks.next(), right_chunks.next()) {
+            (None, None) => return left.cmp(right),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(left), Some(right)) => cmp_chunk(left, right),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+fn cmp_chunk(left: &str, right: &str) -> Ordering {
+    let is_number = |chunk: &str| chunk.starts_with(|c: char| c.is_ascii_digit());
+    match (is_number(left), is_number(right)) {
+        (true, true) => {
+            let (left_digits, right_digits) =
+                (left.trim_start_matches('0'), right.trim_start_matches('0'));
+            left_digits
+                .len()
+                .cmp(&right_digits.len())
+                .then_with(|| left_digits.cmp(right_digits))
+        }
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        (false, false) => left.to_lowercase().cmp(&right.to_lowercase()),
+    }
+}
+
+/// Splits a text into runs of ascii digits and runs of other characters.
+struct Chunks<'a>(&'a str);
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = self.0.chars().next()?;
+        let is_digit = first.is_ascii_digit();
+        let end = self
+            .0
+            .find(|c: char| c.is_ascii_digit() != is_digit)
+            .unwrap_or(self.0.len());
+        let (chunk, rest) = self.0.split_at(end);
+        self.0 = rest;
+        Some(chunk)
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn compare_names_naturally() {
+        fn assert_case(left: &str, right: &str, expected: Ordering) {
+            let actual = natural_cmp(left, right);
+            assert_eq!(expected, actual, "Left: {} Right: {}", left, right);
+        }
+
+        assert_case("dir2", "dir10", Ordering::Less);
+        assert_case("dir10", "dir2", Ordering::Greater);
+        assert_case("dir02", "dir2", Ordering::Less);
+        assert_case("Api", "backend", Ordering::Less);
+        assert_case("api", "Api", Ordering::Greater);
+        assert_case("api", "api_v2", Ordering::Less);
+        assert_case("2024", "api", Ordering::Less);
+        assert_case("same", "same", Ordering::Equal);
+    }
+}
@@ -0,0 +1,90 @@
+use std::ffi::OsString;
+use std::io::IsTerminal;
+
+use clap::ValueEnum;
+use serde::Deserialize;
+
+/// Decides if the output contains colors via ANSI escape codes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ColorMode {
+    /// Colors only if stdout is a terminal.
+    /// The environment variables "NO_COLOR" and "CLICOLOR_FORCE" are respected.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorMode {
+    pub fn should_colorize(self) -> bool {
+        decide(
+            self,
+            std::env::var_os("NO_COLOR"),
+            std::env::var_os("CLICOLOR_FORCE"),
+            std::io::stdout().is_terminal(),
+        )
+    }
+}
+
+/// An explicit mode always wins over the environment variables.
+/// See <https://no-color.org> and <https://bixense.com/clicolors> for the environment variables.
+fn decide(
+    mode: ColorMode,
+    no_color: Option<OsString>,
+    clicolor_force: Option<OsString>,
+    is_terminal: bool,
+) -> bool {
+    match mode {
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+        ColorMode::Auto => {
+            let is_set = |value: &Option<OsString>| value.as_ref().is_some_and(|v| !v.is_empty());
+            if is_set(&no_color) {
+                false
+            } else if is_set(&clicolor_force) && clicolor_force != Some("0".into()) {
+                true
+            } else {
+                is_terminal
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn decide_if_colors_are_used() {
+        fn assert_case(
+            mode: ColorMode,
+            no_color: Option<&str>,
+            clicolor_force: Option<&str>,
+            is_terminal: bool,
+            expected: bool,
+        ) {
+            let actual = decide(
+                mode,
+                no_color.map(OsString::from),
+                clicolor_force.map(OsString::from),
+                is_terminal,
+            );
+            assert_eq!(
+                expected, actual,
+                "Mode: {:?}, NO_COLOR: {:?}, CLICOLOR_FORCE: {:?}, terminal: {}",
+                mode, no_color, clicolor_force, is_terminal
+            );
+        }
+
+        assert_case(ColorMode::Auto, None, None, true, true);
+        assert_case(ColorMode::Auto, None, None, false, false);
+        assert_case(ColorMode::Auto, Some("1"), None, true, false);
+        assert_case(ColorMode::Auto, Some(""), None, true, true);
+        assert_case(ColorMode::Auto, None, Some("1"), false, true);
+        assert_case(ColorMode::Auto, None, Some("0"), false, false);
+        assert_case(ColorMode::Auto, Some("1"), Some("1"), true, false);
+        assert_case(ColorMode::Always, Some("1"), None, false, true);
+        assert_case(ColorMode::Never, None, Some("1"), true, false);
+    }
+}
@@ -1,6 +1,7 @@
 use super::term_colors;
+use crate::error::AppError;
 use crate::{cli_args::GetParams, favorite_folder_record::FavoriteFolderPath, AppResult};
-use std::io::Write;
+use std::io::{IsTerminal, Write};
 
 pub fn ask_possible_prompt_for_ask_number(
     paths: &[FavoriteFolderPath],
@@ -16,10 +17,10 @@ pub fn ask_possible_prompt_for_ask_number(
     let parsed_number: usize = user_input
         .trim()
         .parse()
-        .map_err(|_| gen_req_number_message(len))?;
+        .map_err(|_| AppError::InvalidInput(gen_req_number_message(len)))?;
 
     if parsed_number == 0 || parsed_number > len {
-        Err(gen_req_number_message(len).into())
+        Err(AppError::InvalidInput(gen_req_number_message(len)))
     } else {
         Ok(Some(parsed_number))
     }
@@ -37,6 +38,16 @@ pub fn read_line_from_user(prompt: &str) -> AppResult<String> {
     Ok(buffer)
 }
 
+/// Asks the user to answer with yes. Confirms without asking if stdin is not a terminal
+/// so scripts are not blocked.
+pub fn confirm_if_terminal(question: &str) -> AppResult<bool> {
+    if !std::io::stdin().is_terminal() {
+        return Ok(true);
+    }
+    let answer = read_line_from_user(&format!("{} [y/N]: ", question))?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
 fn gen_req_number_message(max: usize) -> String {
     format!("Number is not between {} and {}", 1, max)
 }
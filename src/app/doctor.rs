@@ -0,0 +1,66 @@
+use std::io::Write;
+use std::path::Path;
+
+use super::term_colors;
+use crate::favorite_table::{display_width, pad_from_right_until};
+use crate::{clipboard, config, file_access, paths, AppResult};
+
+/// Prints a report about the config file, the data file and the clipboard.
+/// Problems are part of the report instead of failing the subcommand.
+pub fn handle_doctor_subcommand(config_path: &Path) -> AppResult {
+    let config = if !config_path.exists() {
+        "not existing, defaults are used".to_string()
+    } else {
+        match config::load(config_path) {
+            Ok(_) => "valid".to_string(),
+            Err(error) => term_colors::color_error_msg(&error.to_string()),
+        }
+    };
+    let files = vec![
+        ("Config file", config_path.to_string_lossy().into_owned()),
+        ("Config", config),
+        data_file_finding(),
+        favorites_finding(),
+    ];
+
+    let mut stdout = std::io::stdout().lock();
+    writeln!(stdout, "{}", term_colors::color_header_msg("Files"))?;
+    print_findings(&mut stdout, &files)?;
+    writeln!(stdout)?;
+    writeln!(stdout, "{}", term_colors::color_header_msg("Clipboard"))?;
+    print_findings(&mut stdout, &clipboard::diagnose())?;
+    Ok(())
+}
+
+fn data_file_finding() -> (&'static str, String) {
+    let data_file = match paths::get_path_to_data() {
+        Ok(path) => path.to_string_lossy().into_owned(),
+        Err(error) => term_colors::color_error_msg(&error.to_string()),
+    };
+    ("Data file", data_file)
+}
+
+fn favorites_finding() -> (&'static str, String) {
+    let favorites = match file_access::get_favorites() {
+        Ok(favorites) => format!("{} saved", favorites.as_slice().len()),
+        Err(error) => term_colors::color_error_msg(&error.to_string()),
+    };
+    ("Favorites", favorites)
+}
+
+fn print_findings(to: &mut impl Write, findings: &[(&str, String)]) -> AppResult {
+    let width = findings
+        .iter()
+        .map(|(label, _)| display_width(label))
+        .max()
+        .unwrap_or_default();
+    for (label, description) in findings {
+        writeln!(
+            to,
+            "  {}  {}",
+            pad_from_right_until(label, width),
+            description
+        )?;
+    }
+    Ok(())
+}
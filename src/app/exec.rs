@@ -0,0 +1,41 @@
+use log::info;
+
+use crate::cli_args::ExecParams;
+use crate::error::AppError;
+use crate::launch::{self, CommandLine};
+use crate::AppResult;
+
+/// Name of the environment variable with the name of the favorite for "--env".
+pub const NAME_ENV: &str = "FAV_NAME";
+/// Name of the environment variable with the location of the favorite for "--env".
+pub const PATH_ENV: &str = "FAV_PATH";
+
+/// Runs the command with the stdio of this app and returns its exit code.
+pub fn handle_exec_subcommand(params: &ExecParams) -> AppResult<i32> {
+    let (name, path) = super::resolve_location(params.get_name())?;
+    if !path.exists() {
+        return Err(AppError::NotFound(format!(
+            "Location {:?} does not exist",
+            path
+        )));
+    }
+    let working_dir = launch::working_dir(&path);
+
+    // Clap ensures that at least one word is given after "--".
+    let (program, args) = params.get_command().split_first().unwrap();
+    let command_line = CommandLine::new(program.clone(), args.to_vec(), working_dir.into());
+    let mut command = command_line.to_command();
+    if params.copy_env() {
+        command.env(NAME_ENV, &name).env(PATH_ENV, &path);
+    }
+
+    info!("Running within {:?}: {}", working_dir, command_line);
+    let status = command.status().map_err(|error| {
+        AppError::Launch(format!(
+            "Could not start \"{}\".\n Details: {}",
+            command_line, error
+        ))
+    })?;
+    super::record_usage(&name);
+    Ok(launch::exit_code_of(status))
+}
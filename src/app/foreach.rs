@@ -0,0 +1,203 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use log::info;
+
+use super::exec::{NAME_ENV, PATH_ENV};
+use super::term_colors;
+use crate::cli_args::ForeachParams;
+use crate::error::AppError;
+use crate::favorite_table::{display_width, pad_from_right_until};
+use crate::launch::{self, CommandLine};
+use crate::{file_access, AppResult};
+
+/// Location of one favorite the command is run in.
+#[derive(Debug)]
+struct Job {
+    name: String,
+    path: PathBuf,
+}
+
+/// How running the command within one location ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Outcome {
+    Succeeded,
+    Failed(i32),
+    Missing,
+    NotStarted(String),
+}
+
+impl Outcome {
+    fn is_success(&self) -> bool {
+        *self == Outcome::Succeeded
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Outcome::Succeeded => "ok".to_string(),
+            Outcome::Failed(code) => format!("failed with exit code {}", code),
+            Outcome::Missing => "location does not exist".to_string(),
+            Outcome::NotStarted(reason) => format!("could not start: {}", reason),
+        }
+    }
+}
+
+/// Runs the command within every selected location and prints a summary at the end.
+/// Returns 0 if the command succeeded everywhere otherwise 1.
+pub fn handle_foreach_subcommand(params: &ForeachParams) -> AppResult<i32> {
+    let favorites = file_access::get_favorites()?;
+    let favorites = match params.get_tag() {
+        Some(tag) => favorites.filtered_with_tag(tag),
+        None => favorites,
+    };
+    let favorites = match params.get_filter() {
+        Some(filter) => favorites.filtered_containing_name(filter.clone()),
+        None => favorites,
+    };
+    let jobs: Vec<Job> = favorites
+        .as_slice()
+        .iter()
+        .map(|favorite| Job {
+            name: favorite.get_name().to_string(),
+            path: favorite.path(),
+        })
+        .collect();
+    if jobs.is_empty() {
+        return Err(AppError::NotFound(
+            "No names match the given tag or filter or no labels were created so far".to_string(),
+        ));
+    }
+
+    let prefix_width = jobs
+        .iter()
+        .map(|job| display_width(&job.name))
+        .max()
+        .unwrap_or_default()
+        + 2;
+    let outcomes = run_all(&jobs, params, prefix_width);
+
+    let mut stdout = std::io::stdout().lock();
+    writeln!(stdout)?;
+    for (job, outcome) in jobs.iter().zip(&outcomes) {
+        let described = outcome.describe();
+        let described = if outcome.is_success() {
+            term_colors::color_exists_msg(&described)
+        } else {
+            term_colors::color_not_found(&described)
+        };
+        writeln!(
+            stdout,
+            "{}  {}",
+            pad_from_right_until(&job.name, prefix_width - 2),
+            described
+        )?;
+    }
+    let failed = outcomes
+        .iter()
+        .filter(|outcome| !outcome.is_success())
+        .count();
+    writeln!(
+        stdout,
+        "{} succeeded, {} failed",
+        outcomes.len() - failed,
+        failed
+    )?;
+
+    Ok(if failed == 0 { 0 } else { 1 })
+}
+
+/// Runs up to "--parallel" commands at the same time.
+/// Outcomes are returned in the order of the jobs.
+fn run_all(jobs: &[Job], params: &ForeachParams, prefix_width: usize) -> Vec<Outcome> {
+    let next_job = AtomicUsize::new(0);
+    let outcomes = Mutex::new(vec![None; jobs.len()]);
+    let workers = params.copy_parallel().get().min(jobs.len());
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let index = next_job.fetch_add(1, Ordering::SeqCst);
+                let Some(job) = jobs.get(index) else {
+                    break;
+                };
+                let outcome = run_one(job, params, prefix_width);
+                outcomes.lock().unwrap()[index] = Some(outcome);
+            });
+        }
+    });
+    outcomes
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|outcome| outcome.expect("Every job is run by a worker"))
+        .collect()
+}
+
+fn run_one(job: &Job, params: &ForeachParams, prefix_width: usize) -> Outcome {
+    if !job.path.exists() {
+        return Outcome::Missing;
+    }
+
+    // Clap ensures that at least one word is given after "--".
+    let (program, args) = params.get_command().split_first().unwrap();
+    let working_dir = launch::working_dir(&job.path);
+    let command_line = CommandLine::new(program.clone(), args.to_vec(), working_dir.into());
+    let mut command = command_line.to_command();
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if params.copy_env() {
+        command.env(NAME_ENV, &job.name).env(PATH_ENV, &job.path);
+    }
+
+    info!("Running within {:?}: {}", working_dir, command_line);
+    match run_with_prefix(command, &job.name, prefix_width) {
+        Ok(0) => Outcome::Succeeded,
+        Ok(code) => Outcome::Failed(code),
+        Err(error) => Outcome::NotStarted(error.to_string()),
+    }
+}
+
+/// Every line of stdout and stderr of the command starts with the name like "[api] ".
+fn run_with_prefix(mut command: Command, name: &str, prefix_width: usize) -> std::io::Result<i32> {
+    let mut child = command.spawn()?;
+    let prefix = format!("[{}]", name);
+    let padding = " ".repeat(prefix_width.saturating_sub(display_width(&prefix)));
+    let prefix = term_colors::color_name_msg(&prefix, None) + &padding;
+    let stdout = child.stdout.take().expect("Stdout is piped");
+    let stderr = child.stderr.take().expect("Stderr is piped");
+    std::thread::scope(|scope| {
+        scope.spawn(|| forward_lines(stdout, std::io::stdout(), &prefix));
+        scope.spawn(|| forward_lines(stderr, std::io::stderr(), &prefix));
+    });
+    child.wait().map(launch::exit_code_of)
+}
+
+/// Lines are forwarded as bytes so output which is not valid utf 8 stays intact.
+fn forward_lines(from: impl Read, mut to: impl Write, prefix: &str) {
+    for line in BufReader::new(from).split(b'\n') {
+        let Ok(line) = line else {
+            return;
+        };
+        let mut prefixed = format!("{} ", prefix).into_bytes();
+        prefixed.extend_from_slice(&line);
+        prefixed.push(b'\n');
+        // Output is lost anyway if it can not be written.
+        let _ = to.write_all(&prefixed);
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn prefix_every_line_and_keep_invalid_utf8() {
+        let mut actual = Vec::new();
+        forward_lines(&b"first\nsec\xffond"[..], &mut actual, "[api]");
+        assert_eq!(b"[api] first\n[api] sec\xffond\n".to_vec(), actual);
+    }
+}
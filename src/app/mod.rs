@@ -1,54 +1,123 @@
-use log::error;
+use log::{error, warn};
 
+use crate::clipboard::ClipboardOptions;
+use crate::error::{AppError, ErrorFormat};
 use crate::favorite_folder_record::FavoriteFolderPath;
+use crate::favorite_table::DrawParam;
+use crate::output_format::{self, FavoriteReport, OutputFormat};
 use crate::{cli_args::GetParams, favorite_table};
-use std::error::Error;
+use std::ffi::{OsStr, OsString};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::OnceLock;
 
-use crate::{clipboard, file_access, AppResult};
+use crate::{clipboard, data_access, file_access, AppResult};
 
+pub mod color_mode;
 pub mod console_interaction;
+pub mod doctor;
+pub mod exec;
+pub mod foreach;
+pub mod open;
 pub mod term_colors;
-
-pub fn put_into_clipboard_or_print(content: &str, clipboard: bool) -> AppResult {
-    if clipboard {
-        clipboard::put_into_clipboard(content)?;
-    } else {
-        println!("{content}");
+pub mod theme;
+
+/// Content is printed if no options for the clipboard are given.
+pub fn put_into_clipboard_or_print(
+    content: &OsStr,
+    clipboard: Option<ClipboardOptions>,
+) -> AppResult {
+    match clipboard {
+        Some(options) => {
+            let content = content.to_str().ok_or_else(|| {
+                AppError::InvalidInput(
+                    "Location is not a valid utf 8 text and can not be put into the clipboard"
+                        .to_string(),
+                )
+            })?;
+            clipboard::put_into_clipboard(content, options)?;
+        }
+        None => print_os_str(content)?,
     }
 
     Ok(())
 }
 
-pub fn exit_with_error(message: &dyn Error) {
-    let red_msg = term_colors::color_error_msg(&format!("Error: {message}"));
-    error!("{}", red_msg);
-    std::process::exit(1);
+/// Prints the content with a new line without losing bytes which are not valid utf 8 on Unix.
+pub fn print_os_str(content: &OsStr) -> AppResult {
+    let mut stdout = std::io::stdout().lock();
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        stdout.write_all(content.as_bytes())?;
+    }
+    #[cfg(not(unix))]
+    {
+        stdout.write_all(content.to_string_lossy().as_bytes())?;
+    }
+    stdout.write_all(b"\n")?;
+    Ok(())
+}
+
+static ERROR_FORMAT: OnceLock<ErrorFormat> = OnceLock::new();
+
+/// Only the first call has an effect.
+pub fn use_error_format(format: ErrorFormat) {
+    let _ = ERROR_FORMAT.set(format);
 }
 
-pub fn handle_get_subcommand(get_params: &GetParams) -> AppResult<String> {
+/// Exits with the exit code of the category of the error.
+pub fn exit_with_error(error: &AppError) -> ! {
+    match ERROR_FORMAT.get().copied().unwrap_or_default() {
+        ErrorFormat::Text => {
+            let red_msg = term_colors::color_error_msg(&format!("Error: {error}"));
+            error!("{}", red_msg);
+        }
+        ErrorFormat::Json => eprintln!("{}", error.to_json()),
+    }
+    std::process::exit(error.exit_code());
+}
+
+/// `colors` decides if the output may contain colors.
+/// Content for the clipboard never contains colors.
+pub fn handle_get_subcommand(get_params: &GetParams, colors: bool) -> AppResult<OsString> {
+    let colors = colors && !get_params.copy_has_clipboard();
     return match get_params.get_name() {
         Some(name_given) => {
             let favorites = file_access::get_favorites()?;
             let name = name_given.try_into()?;
             let content = match favorites.get(name) {
-                Some(found) => found.path_str(),
+                Some(found) => {
+                    record_usage(found.get_name());
+                    match get_params.copy_format() {
+                        OutputFormat::Table => location_for_output(found, get_params),
+                        OutputFormat::Json => FavoriteReport::new(found).to_json()?.into(),
+                    }
+                }
                 None => {
+                    if let Some((found, joined)) = favorites.get_with_sub_path(name_given) {
+                        let content = output_sub_path(found, joined, get_params, colors)?;
+                        record_usage(found.get_name());
+                        return Ok(content);
+                    }
                     return match (get_params.copy_fuzzy(), get_params.copy_ask_number()) {
-                        (false, false) => {
-                            Err(format!("No path found for the name: {}", name_given).into())
-                        }
-                        (_, _) => get_all(get_params),
+                        (false, false) => Err(AppError::NotFound(format!(
+                            "No path found for the name: {}",
+                            name_given
+                        ))),
+                        (_, _) => get_all(get_params, colors),
                     };
                 }
             };
 
-            Ok(content.to_string())
+            Ok(content)
         }
-        None => get_all(get_params),
+        None => get_all(get_params, colors),
     };
 
-    fn get_all(get_params: &GetParams) -> AppResult<String> {
-        let all_locations = file_access::get_favorites()?;
+    fn get_all(get_params: &GetParams, colors: bool) -> AppResult<OsString> {
+        let mut all_locations = file_access::get_favorites()?;
+        all_locations.sort(get_params.copy_sort(), get_params.copy_reverse());
 
         let all_locations = if let Some(name) = get_params.get_name() {
             if get_params.copy_fuzzy() {
@@ -61,25 +130,86 @@ pub fn handle_get_subcommand(get_params: &GetParams) -> AppResult<String> {
             all_locations
         };
 
-        let content = draw_table_and_prompt(all_locations.as_slice(), get_params)?;
+        let content = draw_table_and_prompt(all_locations.as_slice(), get_params, colors)?;
 
         Ok(content)
     }
 }
 
+/// Name and expanded location of a name which can be followed by a relative path
+/// like "<name>/<relative/path>".
+pub fn resolve_location(name: &str) -> AppResult<(String, PathBuf)> {
+    let favorites = file_access::get_favorites()?;
+    if let Some(found) = favorites.get(name.try_into()?) {
+        return Ok((found.get_name().to_string(), found.path()));
+    }
+    favorites
+        .get_with_sub_path(name)
+        .map(|(found, joined)| (found.get_name().to_string(), joined))
+        .ok_or_else(|| AppError::NotFound(format!("No path found for the name: {}", name)))
+}
+
+/// Failing to remember the usage of a name should not prevent the output of its location.
+fn record_usage(name: &str) {
+    if let Err(error) = data_access::mark_as_used(name) {
+        warn!(
+            "Could not remember usage of name {}.\n Details: {}",
+            name, error
+        );
+    }
+}
+
+fn location_for_output(favorite: &FavoriteFolderPath, get_params: &GetParams) -> OsString {
+    if get_params.copy_raw() {
+        favorite.location().as_os_str().to_os_string()
+    } else {
+        favorite.expanded_location().into_owned()
+    }
+}
+
+fn output_sub_path(
+    found: &FavoriteFolderPath,
+    joined: PathBuf,
+    get_params: &GetParams,
+    colors: bool,
+) -> AppResult<OsString> {
+    let exists = joined.exists();
+    if !exists && get_params.copy_must_exist() {
+        return Err(AppError::NotFound(format!(
+            "Location {:?} does not exist",
+            joined
+        )));
+    }
+    if get_params.copy_format() == OutputFormat::Json {
+        return Ok(FavoriteReport::with_path(found, joined).to_json()?.into());
+    }
+
+    let content = match (colors, exists) {
+        (false, _) => return Ok(joined.as_os_str().to_os_string()),
+        (true, true) => term_colors::color_exists_msg(&joined.to_string_lossy()),
+        (true, false) => term_colors::color_not_found(&joined.to_string_lossy()),
+    };
+    Ok(content.into())
+}
+
 fn draw_table_and_prompt(
     all_locations: &[FavoriteFolderPath],
     get_params: &GetParams,
-) -> AppResult<String> {
+    colors: bool,
+) -> AppResult<OsString> {
+    if get_params.copy_format() == OutputFormat::Json {
+        return Ok(output_format::favorites_to_json(all_locations)?.into());
+    }
     if all_locations.is_empty() {
-        return Err(
-            "No match found for given name or no labels were created so far"
-                .to_string()
-                .into(),
-        );
+        return Err(AppError::NotFound(
+            "No match found for given name or no labels were created so far".to_string(),
+        ));
     }
 
-    let table = favorite_table::draw_favorite_table(all_locations, get_params.into());
+    let table = favorite_table::draw_favorite_table(
+        all_locations,
+        DrawParam::from_get_params(get_params, colors),
+    );
     if get_params.copy_ask_number() {
         println!("{table}");
 
@@ -91,13 +221,15 @@ fn draw_table_and_prompt(
                 let index = index_start_from_one - 1;
                 // function for asking number of user ensures that the index will not
                 // be out of bounds
-                let to_put = all_locations.get(index).unwrap().path_str().to_string();
+                let selected = all_locations.get(index).unwrap();
+                record_usage(selected.get_name());
+                let to_put = location_for_output(selected, get_params);
 
                 Ok(to_put)
             }
-            None => Ok(table),
+            None => Ok(table.into()),
         }
     } else {
-        Ok(table)
+        Ok(table.into())
     }
 }
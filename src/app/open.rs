@@ -0,0 +1,98 @@
+use std::path::Path;
+
+use log::info;
+
+use crate::cli_args::OpenParams;
+use crate::config::OpenConfig;
+use crate::error::AppError;
+use crate::launch::{self, CommandLine, OpenWith, Placeholders};
+use crate::AppResult;
+
+/// Opens the location of a name or only prints the command line with "--print".
+pub fn handle_open_subcommand(params: &OpenParams, config: &OpenConfig) -> AppResult {
+    let (name, path) = super::resolve_location(params.get_name())?;
+    if !path.exists() {
+        return Err(AppError::NotFound(format!(
+            "Location {:?} does not exist",
+            path
+        )));
+    }
+    let command_line = command_line_for(&params.open_with(), config, &name, &path)?;
+    if params.copy_print() {
+        println!("{}", command_line);
+        return Ok(());
+    }
+
+    info!("Opening {:?} via: {}", path, command_line);
+    let status = command_line.run().map_err(|error| {
+        AppError::Launch(format!(
+            "Could not start \"{}\".\n Details: {}",
+            command_line, error
+        ))
+    })?;
+    super::record_usage(&name);
+    if status.success() {
+        Ok(())
+    } else {
+        Err(AppError::Launch(format!(
+            "\"{}\" failed with {}",
+            command_line, status
+        )))
+    }
+}
+
+/// A command line from the config file takes precedence over environment variables and
+/// the defaults of the OS.
+fn command_line_for(
+    with: &OpenWith,
+    config: &OpenConfig,
+    name: &str,
+    path: &Path,
+) -> AppResult<CommandLine> {
+    let from_env = |variables: &[&str]| {
+        variables.iter().find_map(|variable| {
+            std::env::var(variable)
+                .ok()
+                .filter(|value| !value.is_empty())
+        })
+    };
+    let template = match with {
+        OpenWith::System => config
+            .default
+            .clone()
+            .unwrap_or_else(|| launch::system_opener().to_string()),
+        OpenWith::Editor => config
+            .editor
+            .clone()
+            .or_else(|| from_env(&["VISUAL", "EDITOR"]))
+            .ok_or_else(|| {
+                AppError::Launch(
+                    "No editor found. Set VISUAL, EDITOR or \"open.editor\" in the config file"
+                        .to_string(),
+                )
+            })?,
+        OpenWith::FileManager => config
+            .file_manager
+            .clone()
+            .unwrap_or_else(|| launch::system_opener().to_string()),
+        OpenWith::Terminal => config
+            .terminal
+            .clone()
+            .or_else(|| from_env(&["TERMINAL"]))
+            .or_else(|| cfg!(target_os = "macos").then(|| "open -a Terminal {dir}".to_string()))
+            .ok_or_else(|| {
+                AppError::Launch(
+                    "No terminal found. Set TERMINAL or \"open.terminal\" in the config file"
+                        .to_string(),
+                )
+            })?,
+        OpenWith::Custom(command_line) => command_line.clone(),
+    };
+
+    let placeholders = Placeholders::new(name, path);
+    Ok(CommandLine::from_template(
+        &template,
+        placeholders,
+        with.to_append(),
+    )?)
+}
@@ -1,14 +1,70 @@
-use colored::*;
+use std::sync::OnceLock;
+
+use super::theme::Theme;
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+/// Sets the theme for all following colored output.
+/// Only the first call has an effect. Without any call the default theme is used.
+pub fn use_theme(theme: Theme) {
+    let _ = THEME.set(theme);
+}
+
+fn theme() -> &'static Theme {
+    THEME.get_or_init(Theme::default)
+}
 
 pub fn color_prompt_msg(to_color: &str) -> String {
-    to_color.blue().to_string()
+    theme().prompt.paint(to_color)
 }
 pub fn color_error_msg(to_color: &str) -> String {
-    to_color.red().to_string()
+    theme().error.paint(to_color)
 }
 pub fn color_exists_msg(to_color: &str) -> String {
-    to_color.green().to_string()
+    theme().exists.paint(to_color)
 }
 pub fn color_not_found(to_color: &str) -> String {
-    to_color.red().to_string()
+    theme().missing.paint(to_color)
+}
+pub fn color_file_msg(to_color: &str) -> String {
+    theme().file.paint(to_color)
+}
+pub fn color_symlink_msg(to_color: &str) -> String {
+    theme().symlink.paint(to_color)
+}
+pub fn color_broken_symlink_msg(to_color: &str) -> String {
+    theme().broken_symlink.paint(to_color)
+}
+pub fn color_special_msg(to_color: &str) -> String {
+    theme().special.paint(to_color)
+}
+pub fn color_permission_denied_msg(to_color: &str) -> String {
+    theme().permission_denied.paint(to_color)
+}
+pub fn color_io_error_msg(to_color: &str) -> String {
+    theme().io_error.paint(to_color)
+}
+pub fn color_index_msg(to_color: &str) -> String {
+    theme().index.paint(to_color)
+}
+pub fn color_header_msg(to_color: &str) -> String {
+    theme().header.paint(to_color)
+}
+
+/// Parts of the name which match `to_highlight` are shown with the style for matches.
+pub fn color_name_msg(to_color: &str, to_highlight: Option<&str>) -> String {
+    let theme = theme();
+    let Some(to_highlight) = to_highlight.filter(|text| !text.is_empty()) else {
+        return theme.name.paint(to_color);
+    };
+
+    let mut colored = String::new();
+    let mut last_end = 0;
+    for (start, matched) in to_color.match_indices(to_highlight) {
+        colored.push_str(&theme.name.paint(&to_color[last_end..start]));
+        colored.push_str(&theme.highlight.paint(matched));
+        last_end = start + matched.len();
+    }
+    colored.push_str(&theme.name.paint(&to_color[last_end..]));
+    colored
 }
@@ -0,0 +1,395 @@
+use std::str::FromStr;
+
+use clap::ValueEnum;
+use serde::Deserialize;
+use thiserror::Error;
+
+/// Colors and text attributes used for every semantic role of the output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+    pub prompt: Style,
+    pub error: Style,
+    pub exists: Style,
+    pub missing: Style,
+    pub file: Style,
+    pub symlink: Style,
+    pub broken_symlink: Style,
+    pub special: Style,
+    pub permission_denied: Style,
+    pub io_error: Style,
+    pub name: Style,
+    pub index: Style,
+    pub header: Style,
+    /// Part of a name which matches the name given for a fuzzy search.
+    pub highlight: Style,
+}
+
+/// Themes shipped with this app.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BuiltinTheme {
+    /// Colors of the terminal like blue prompts, green existing and red missing paths.
+    #[default]
+    Default,
+    /// Bright and bold colors for dark terminals.
+    HighContrast,
+    /// True colors of the solarized palette.
+    Solarized,
+    /// No colors, only bold, dim and underlined text.
+    Monochrome,
+}
+
+/// Section `[theme]` of the config file.
+/// Every given role replaces the style of the chosen built-in theme.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ThemeConfig {
+    /// Built-in theme to start from. The option "--theme" takes precedence.
+    pub base: Option<BuiltinTheme>,
+    pub prompt: Option<Style>,
+    pub error: Option<Style>,
+    pub exists: Option<Style>,
+    pub missing: Option<Style>,
+    pub file: Option<Style>,
+    pub symlink: Option<Style>,
+    pub broken_symlink: Option<Style>,
+    pub special: Option<Style>,
+    pub permission_denied: Option<Style>,
+    pub io_error: Option<Style>,
+    pub name: Option<Style>,
+    pub index: Option<Style>,
+    pub header: Option<Style>,
+    pub highlight: Option<Style>,
+}
+
+impl Theme {
+    pub fn builtin(theme: BuiltinTheme) -> Self {
+        let [prompt, error, exists, missing, file, symlink, broken_symlink, special, permission_denied, io_error, name, index, header, highlight] =
+            match theme {
+                BuiltinTheme::Default => [
+                    "blue",
+                    "red",
+                    "green",
+                    "red",
+                    "bright_green",
+                    "cyan",
+                    "magenta",
+                    "blue",
+                    "yellow",
+                    "bright_red",
+                    "none",
+                    "none",
+                    "bold",
+                    "bold underline",
+                ],
+                BuiltinTheme::HighContrast => [
+                    "bold bright_cyan",
+                    "bold bright_red",
+                    "bold bright_green",
+                    "bold bright_red",
+                    "bright_green",
+                    "bright_cyan",
+                    "bold bright_magenta",
+                    "bright_blue",
+                    "bold bright_yellow",
+                    "bold bright_red",
+                    "bold bright_white",
+                    "bright_white",
+                    "bold underline bright_white",
+                    "bold underline bright_yellow",
+                ],
+                BuiltinTheme::Solarized => [
+                    "#268bd2",
+                    "#dc322f",
+                    "#859900",
+                    "#dc322f",
+                    "#2aa198",
+                    "#6c71c4",
+                    "#d33682",
+                    "#268bd2",
+                    "#b58900",
+                    "#cb4b16",
+                    "#93a1a1",
+                    "#586e75",
+                    "bold #93a1a1",
+                    "bold #b58900",
+                ],
+                BuiltinTheme::Monochrome => [
+                    "bold",
+                    "bold",
+                    "none",
+                    "dim",
+                    "none",
+                    "underline",
+                    "dim underline",
+                    "none",
+                    "dim",
+                    "dim",
+                    "none",
+                    "dim",
+                    "bold",
+                    "underline",
+                ],
+            }
+            .map(|style| {
+                style
+                    .parse::<Style>()
+                    .expect("Styles of built-in themes are valid")
+            });
+        Self {
+            prompt,
+            error,
+            exists,
+            missing,
+            file,
+            symlink,
+            broken_symlink,
+            special,
+            permission_denied,
+            io_error,
+            name,
+            index,
+            header,
+            highlight,
+        }
+    }
+
+    /// Starts from the built-in theme given via the cli, the config or the default one
+    /// in this order. Afterwards every role given in the config is applied.
+    pub fn from_config(from_cli: Option<BuiltinTheme>, config: &ThemeConfig) -> Self {
+        let base = from_cli.or(config.base).unwrap_or_default();
+        let mut theme = Self::builtin(base);
+        let overrides = [
+            (&mut theme.prompt, &config.prompt),
+            (&mut theme.error, &config.error),
+            (&mut theme.exists, &config.exists),
+            (&mut theme.missing, &config.missing),
+            (&mut theme.file, &config.file),
+            (&mut theme.symlink, &config.symlink),
+            (&mut theme.broken_symlink, &config.broken_symlink),
+            (&mut theme.special, &config.special),
+            (&mut theme.permission_denied, &config.permission_denied),
+            (&mut theme.io_error, &config.io_error),
+            (&mut theme.name, &config.name),
+            (&mut theme.index, &config.index),
+            (&mut theme.header, &config.header),
+            (&mut theme.highlight, &config.highlight),
+        ];
+        for (style, given) in overrides {
+            if let Some(given) = given {
+                *style = given.clone();
+            }
+        }
+        theme
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::builtin(BuiltinTheme::default())
+    }
+}
+
+/// Foreground color and text attributes written like "bold underline #ff8700".
+/// A color is a name like "red" or "bright_red", a number of the 256 color palette like "208"
+/// or a true color like "#ff8700". "none" stands for plain text.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Style {
+    color: Option<ThemeColor>,
+    bold: bool,
+    dim: bool,
+    underline: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ThemeColor {
+    /// Code of one of the 16 named colors.
+    Named(u8),
+    Ansi256(u8),
+    TrueColor {
+        r: u8,
+        g: u8,
+        b: u8,
+    },
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum InvalidStyle {
+    #[error(
+        "Unknown part \"{0}\" of a style. Expected bold, dim, underline, none, \
+        a color name like \"bright_red\", a number up to 255 or a hex color like \"#ff8700\""
+    )]
+    UnknownPart(String),
+    #[error("Style \"{0}\" contains more than one color")]
+    MoreThanOneColor(String),
+}
+
+const NAMED_COLORS: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+impl Style {
+    /// Surrounds the text with the escape codes of this style.
+    /// The text is returned unchanged if colors are turned off.
+    pub fn paint(&self, text: &str) -> String {
+        if text.is_empty()
+            || self.is_plain()
+            || !colored::control::SHOULD_COLORIZE.should_colorize()
+        {
+            return text.to_string();
+        }
+        format!("\x1b[{}m{}\x1b[0m", self.escape_codes().join(";"), text)
+    }
+
+    fn is_plain(&self) -> bool {
+        *self == Self::default()
+    }
+
+    fn escape_codes(&self) -> Vec<String> {
+        let mut codes = Vec::new();
+        for (is_set, code) in [(self.bold, "1"), (self.dim, "2"), (self.underline, "4")] {
+            if is_set {
+                codes.push(code.to_string());
+            }
+        }
+        match self.color {
+            Some(ThemeColor::Named(code)) => codes.push(code.to_string()),
+            Some(ThemeColor::Ansi256(number)) => codes.push(format!("38;5;{}", number)),
+            Some(ThemeColor::TrueColor { r, g, b }) => {
+                codes.push(format!("38;2;{};{};{}", r, g, b))
+            }
+            None => (),
+        }
+        codes
+    }
+}
+
+impl FromStr for Style {
+    type Err = InvalidStyle;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut style = Self::default();
+        for part in s.split_whitespace() {
+            let part = part.to_lowercase();
+            match part.as_str() {
+                "none" => (),
+                "bold" => style.bold = true,
+                "dim" => style.dim = true,
+                "underline" => style.underline = true,
+                color => {
+                    let color = parse_color(color)
+                        .ok_or_else(|| InvalidStyle::UnknownPart(part.clone()))?;
+                    if style.color.replace(color).is_some() {
+                        return Err(InvalidStyle::MoreThanOneColor(s.to_string()));
+                    }
+                }
+            }
+        }
+        Ok(style)
+    }
+}
+
+impl TryFrom<String> for Style {
+    type Error = InvalidStyle;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+fn parse_color(color: &str) -> Option<ThemeColor> {
+    if let Some(hex) = color.strip_prefix('#') {
+        let channel = |range| {
+            hex.get(range)
+                .and_then(|channel| u8::from_str_radix(channel, 16).ok())
+        };
+        return match (hex.len(), channel(0..2), channel(2..4), channel(4..6)) {
+            (6, Some(r), Some(g), Some(b)) => Some(ThemeColor::TrueColor { r, g, b }),
+            _ => None,
+        };
+    }
+    if let Ok(number) = color.parse::<u8>() {
+        return Some(ThemeColor::Ansi256(number));
+    }
+
+    let (name, offset) = match color
+        .strip_prefix("bright_")
+        .or_else(|| color.strip_prefix("bright-"))
+    {
+        Some(name) => (name, 90),
+        None => (color, 30),
+    };
+    NAMED_COLORS
+        .iter()
+        .position(|named| *named == name)
+        .map(|position| ThemeColor::Named(offset + position as u8))
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn parse_styles() {
+        fn assert_case(given: &str, expected: &[&str]) {
+            let actual = given.parse::<Style>().unwrap().escape_codes();
+            assert_eq!(expected, actual, "Given: {}", given);
+        }
+
+        assert_case("none", &[]);
+        assert_case("red", &["31"]);
+        assert_case("bright_blue", &["94"]);
+        assert_case("Bright-White", &["97"]);
+        assert_case("bold underline 208", &["1", "4", "38;5;208"]);
+        assert_case("dim #FF8700", &["2", "38;2;255;135;0"]);
+    }
+
+    #[test]
+    fn reject_invalid_styles() {
+        fn assert_case(given: &str, expected: InvalidStyle) {
+            let actual = given.parse::<Style>().unwrap_err();
+            assert_eq!(expected, actual, "Given: {}", given);
+        }
+
+        assert_case("orange", InvalidStyle::UnknownPart("orange".to_string()));
+        assert_case("256", InvalidStyle::UnknownPart("256".to_string()));
+        assert_case("#ff87", InvalidStyle::UnknownPart("#ff87".to_string()));
+        assert_case(
+            "red blue",
+            InvalidStyle::MoreThanOneColor("red blue".to_string()),
+        );
+    }
+
+    #[test]
+    fn apply_roles_of_config_on_built_in_theme() {
+        let config: ThemeConfig = toml::from_str(
+            r##"
+            base = "monochrome"
+            missing = "bold #ff0000"
+            "##,
+        )
+        .unwrap();
+
+        let actual = Theme::from_config(None, &config);
+        let mut expected = Theme::builtin(BuiltinTheme::Monochrome);
+        expected.missing = "bold #ff0000".parse().unwrap();
+        assert_eq!(expected, actual);
+
+        let actual = Theme::from_config(Some(BuiltinTheme::Solarized), &config);
+        assert_eq!(
+            Theme::builtin(BuiltinTheme::Solarized).prompt,
+            actual.prompt
+        );
+        assert_eq!(expected.missing, actual.missing);
+    }
+
+    #[test]
+    fn point_at_invalid_role_in_config() {
+        let actual = toml::from_str::<ThemeConfig>(r#"missing = "bold orange""#)
+            .unwrap_err()
+            .to_string();
+        assert!(actual.contains("missing"), "Actual: {}", actual);
+        assert!(actual.contains("orange"), "Actual: {}", actual);
+    }
+}
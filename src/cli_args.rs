@@ -1,10 +1,25 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use std::ffi::OsString;
+use std::num::{NonZeroU64, NonZeroUsize};
+use std::path::PathBuf;
+use std::time::Duration;
 
+use crate::app::color_mode::ColorMode;
+use crate::app::theme::BuiltinTheme;
+
+use crate::all_favorites::SortBy;
+use crate::clipboard::{ClipboardBackend, ClipboardOptions, ClipboardTarget};
+use crate::config::{self, AppConfig};
+use crate::data_access::Overwrite;
+use crate::error::ErrorFormat;
+use crate::favorite_table::{BorderStyle, Column, ColumnAlignment, HyperlinkMode};
+use crate::launch::OpenWith;
+use crate::output_format::OutputFormat;
 use crate::trimmed_not_empty_text::NonEmptyText;
 
 /// Structs to define the allowed and passable arguments for app over cli
 /// All positional arguments are validated to be non-empty or not only whitespaces
-/// The argument is made up of subcommands
+/// The argument is made up of global options and subcommands
 #[derive(Parser, Debug)]
 #[command(
     author = "BoolPurist",
@@ -12,10 +27,40 @@ use crate::trimmed_not_empty_text::NonEmptyText;
     about = "Tool to save and load paths to file or folders",
     propagate_version = true
 )]
+pub struct CliArgs {
+    #[command(subcommand)]
+    pub command: CliCommands,
+    #[arg(long, global = true, value_enum)]
+    /// Decides if the output contains colors. Defaults to "auto".
+    /// "auto" uses colors only if stdout is a terminal and respects NO_COLOR and CLICOLOR_FORCE.
+    pub color: Option<ColorMode>,
+    #[arg(long, global = true, value_enum)]
+    /// Built-in color theme. Takes precedence over the theme chosen in the config file.
+    /// Colors of single roles can still be changed in the section "[theme]" of the config file.
+    pub theme: Option<BuiltinTheme>,
+    #[arg(long, global = true)]
+    /// Path to the config file instead of "config.toml" in the typical config folder of the OS.
+    pub config: Option<PathBuf>,
+    #[arg(long, global = true, value_enum)]
+    /// How the clipboard is reached. Defaults to "auto" which uses the escape sequence OSC 52
+    /// of the terminal if no X11 or Wayland display is available like via SSH.
+    pub clipboard_backend: Option<ClipboardBackend>,
+    #[arg(long, global = true, value_enum, default_value_t)]
+    /// Prints errors as text or as JSON object with kind, exit code and message on stderr.
+    pub error_format: ErrorFormat,
+    #[arg(long, global = true)]
+    /// Only prints how the saved favorites or the config file would change
+    /// without saving anything.
+    pub dry_run: bool,
+}
+
+#[derive(Subcommand, Debug)]
 pub enum CliCommands {
     #[command(visible_alias = "g")]
     /// Outputs location of given name or all paths if no name is given.
-    /// Location to existing files/folders will be shown in green otherwise red.
+    /// Location to existing folders will be shown in green, files in bright green,
+    /// symbolic links in cyan, broken symbolic links in magenta, inaccessible locations in yellow
+    /// and missing ones in red.
     Get(GetParams),
     #[command(visible_alias = "r")]
     /// Changes name of favorite path.
@@ -24,6 +69,9 @@ pub enum CliCommands {
         old_name_favorite: NonEmptyText,
         /// new name/label to use for a location.
         new_name_favorite: NonEmptyText,
+        #[arg(short, long)]
+        /// Replaces the location under the new name if the new name is already used.
+        force: bool,
     },
     #[command(visible_alias = "s")]
     /// Creates or changes location under a given name.
@@ -31,25 +79,241 @@ pub enum CliCommands {
         /// Name for a new or existing location.
         name_favorite: NonEmptyText,
         /// Location under the new or new location under a existing name.
-        new_path: NonEmptyText,
+        /// A relative location is resolved against the current working directory.
+        #[arg(required_unless_present = "from_clipboard")]
+        new_path: Option<OsString>,
+        #[arg(long, conflicts_with = "new_path")]
+        /// Takes the location from the clipboard.
+        from_clipboard: bool,
+        #[arg(long, requires = "from_clipboard")]
+        /// Accepts text from the clipboard even if it spans several lines or does not look
+        /// like a path.
+        force: bool,
+        #[arg(long)]
+        /// Resolves symbolic links and saves the canonical form of the location.
+        canonicalize: bool,
+        #[arg(short, long)]
+        /// Fails if the location does not exist instead of only warning about it.
+        must_exist: bool,
+        #[command(flatten)]
+        overwrite: OverwriteParams,
     },
     #[command(visible_alias = "d")]
     /// Removes given name with its path. Note: The location on your files system will not be
-    /// removed of course.
+    /// removed of course. A backup of the data file is saved before.
     Delete {
         /// Name with its location to be removed.
         name_favorite: NonEmptyText,
     },
     /// Removes all non-existing paths.
+    /// Asks for confirmation if more than 3 favorites would be removed.
+    /// A backup of the data file is saved before.
     #[command(visible_alias = "c")]
-    Clean,
+    Clean {
+        #[arg(short, long)]
+        /// Removes without asking for confirmation.
+        yes: bool,
+    },
     #[command(visible_alias = "p")]
     /// Creates or changes path under given label with current working directory
     PwdSet {
         /// New name or existing name under which the current working directory is to be written.
         name_favorite: NonEmptyText,
+        #[command(flatten)]
+        overwrite: OverwriteParams,
+    },
+    #[command(visible_alias = "t")]
+    /// Adds tags to a name for selecting several names at once like "foreach --tag work".
+    Tag {
+        /// Name to be tagged.
+        name_favorite: NonEmptyText,
+        #[arg(required = true)]
+        /// Tags to add or remove.
+        tags: Vec<NonEmptyText>,
+        #[arg(short, long)]
+        /// Removes the tags instead of adding them.
+        remove: bool,
+    },
+    /// Creates or changes the location under given name with the text in the clipboard.
+    ClipSet {
+        /// New name or existing name under which the location from the clipboard is written.
+        name_favorite: NonEmptyText,
+        #[arg(long)]
+        /// Accepts text from the clipboard even if it spans several lines or does not look
+        /// like a path.
+        force: bool,
+        #[command(flatten)]
+        overwrite: OverwriteParams,
     },
-    Reset,
+    /// Removes all favorites after confirmation. A backup of the data file is saved before.
+    Reset {
+        #[arg(short, long)]
+        /// Removes without asking for confirmation.
+        yes: bool,
+    },
+    #[command(visible_alias = "o")]
+    /// Opens the location of a name with the app registered in the OS, an editor,
+    /// a file manager, a terminal or a given command.
+    Open(OpenParams),
+    #[command(visible_alias = "x")]
+    /// Runs a command within the location of a name like "exec api -- cargo test".
+    /// The folder containing the location is used if the location is a file.
+    /// Exits with the exit code of the command.
+    Exec(ExecParams),
+    #[command(visible_alias = "f")]
+    /// Runs a command within the location of every name like "foreach -- git pull".
+    /// Every line of output starts with the name. A summary is shown at the end.
+    /// Exits with 1 if the command failed for any name.
+    Foreach(ForeachParams),
+    /// Shows or changes the defaults in the config file.
+    /// Options given via cli take precedence over the config file.
+    #[command(subcommand)]
+    Config(ConfigCommands),
+    /// Shows the used config file, data file and how the clipboard is reached.
+    /// Helps to find out why something does not work as expected.
+    Doctor,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommands {
+    /// Outputs the value of a key like "get.sort".
+    Get {
+        /// Key like "color", "data_file", "get.format", "get.sort", "get.fuzzy", "get.clipboard",
+        /// "table.spacing", "table.hyperlinks", "open.<default|editor|file_manager|terminal>",
+        /// "clipboard.<clear_after|backend|command|paste_command>", "theme.base" or "theme.<role>".
+        key: String,
+    },
+    /// Sets the value of a key. The config file is only changed if it stays valid.
+    Set {
+        /// Key like "color", "data_file", "get.format", "get.sort", "get.fuzzy", "get.clipboard",
+        /// "table.spacing", "table.hyperlinks", "open.<default|editor|file_manager|terminal>",
+        /// "clipboard.<clear_after|backend|command|paste_command>", "theme.base" or "theme.<role>".
+        key: String,
+        value: String,
+    },
+    /// Lists every key set in the config file with its value.
+    List,
+    /// Outputs the path of the config file. The file does not need to exist.
+    Path,
+}
+
+#[derive(Parser, Debug)]
+pub struct OverwriteParams {
+    #[arg(short, long, conflicts_with = "yes")]
+    /// Fails instead of changing the location of an existing name.
+    no_clobber: bool,
+    #[arg(short, long)]
+    /// Changes the location of an existing name without asking for confirmation.
+    yes: bool,
+}
+
+impl OverwriteParams {
+    /// Without any flag the user is asked before a location is changed.
+    pub fn overwrite(&self) -> Overwrite {
+        match (self.no_clobber, self.yes) {
+            (true, _) => Overwrite::Never,
+            (false, true) => Overwrite::Always,
+            (false, false) => Overwrite::Ask,
+        }
+    }
+}
+
+#[derive(Parser, Debug)]
+pub struct ExecParams {
+    /// Name of the location to run the command in.
+    /// A relative path can be appended after the name like "<name>/<relative/path>".
+    name: String,
+    #[arg(short, long)]
+    /// Sets the environment variables FAV_NAME and FAV_PATH to the name and its location.
+    env: bool,
+    #[arg(last = true, required = true)]
+    /// Command with its arguments after "--".
+    command: Vec<OsString>,
+}
+
+impl ExecParams {
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+    pub fn copy_env(&self) -> bool {
+        self.env
+    }
+    pub fn get_command(&self) -> &[OsString] {
+        &self.command
+    }
+}
+
+#[derive(Parser, Debug)]
+pub struct ForeachParams {
+    #[arg(short, long)]
+    /// Only names with the given tag are selected.
+    tag: Option<NonEmptyText>,
+    #[arg(long)]
+    /// Only names containing the given text are selected.
+    filter: Option<NonEmptyText>,
+    #[arg(short, long, default_value = "1")]
+    /// Number of commands running at the same time.
+    parallel: NonZeroUsize,
+    #[arg(short, long)]
+    /// Sets the environment variables FAV_NAME and FAV_PATH to the name and its location.
+    env: bool,
+    #[arg(last = true, required = true)]
+    /// Command with its arguments after "--".
+    command: Vec<OsString>,
+}
+
+impl ForeachParams {
+    pub fn get_tag(&self) -> Option<&NonEmptyText> {
+        self.tag.as_ref()
+    }
+    pub fn get_filter(&self) -> Option<&NonEmptyText> {
+        self.filter.as_ref()
+    }
+    pub fn copy_parallel(&self) -> NonZeroUsize {
+        self.parallel
+    }
+    pub fn copy_env(&self) -> bool {
+        self.env
+    }
+    pub fn get_command(&self) -> &[OsString] {
+        &self.command
+    }
+}
+
+#[derive(Parser, Debug)]
+pub struct OpenParams {
+    /// Name of the location to open.
+    /// A relative path can be appended after the name like "<name>/<relative/path>".
+    name: String,
+    #[arg(short, long)]
+    /// "system", "editor", "file-manager" or "terminal".
+    /// Defaults to "system" which uses the app registered in the OS.
+    /// "editor" uses VISUAL or EDITOR, "terminal" uses TERMINAL if not set in the config file.
+    with: Option<OpenWith>,
+    #[arg(long, conflicts_with = "with")]
+    /// Command line like "code -n {path}" used instead of the ones chosen via "--with".
+    /// The placeholders "{path}", "{dir}" and "{name}" are replaced.
+    /// "{path}" is appended if no placeholder is given.
+    with_command: Option<String>,
+    #[arg(short, long)]
+    /// Only outputs the command line instead of running it.
+    print: bool,
+}
+
+impl OpenParams {
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+    pub fn open_with(&self) -> OpenWith {
+        match (&self.with_command, &self.with) {
+            (Some(command_line), _) => OpenWith::Custom(command_line.clone()),
+            (None, Some(with)) => with.clone(),
+            (None, None) => OpenWith::System,
+        }
+    }
+    pub fn copy_print(&self) -> bool {
+        self.print
+    }
 }
 
 #[derive(Parser, Debug)]
@@ -57,26 +321,118 @@ pub enum CliCommands {
 pub struct GetParams {
     /// Label/name to get the location from. If left out then all names with their location are
     /// shown.
+    /// A relative path can be appended after the name like "<name>/<relative/path>".
+    /// In this case the relative path is joined onto the location of the name.
     name: Option<String>,
     /// If provided then the output will be written to clipboard instead of stdout.
-    #[arg(short, long)]
-    clipboard: bool,
+    /// "--clipboard=primary" writes into the primary selection pasted via middle click on Linux
+    /// and "--clipboard=both" into both.
+    #[arg(
+        short,
+        long,
+        value_enum,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "clipboard",
+        overrides_with = "no_clipboard"
+    )]
+    clipboard: Option<ClipboardTarget>,
+    /// Writes the output to stdout even if the config file says otherwise.
+    #[arg(long, overrides_with = "clipboard")]
+    no_clipboard: bool,
+    /// Clears the clipboard after the given seconds if it still contains the output.
+    #[arg(long, value_name = "SECONDS")]
+    clear_after: Option<NonZeroU64>,
     /// list all names and paths with line numbers. Waits for one line to accept a line number.
     /// The path of the location with the respective line is then outputted.
     #[arg(short, long)]
     ask_number: bool,
-    #[arg(short, long)]
+    #[arg(short, long, overrides_with = "no_fuzzy")]
     /// if given name is not found then all paths are listed with a name in which the given name
     /// occurs
     fuzzy: bool,
+    #[arg(long, overrides_with = "fuzzy")]
+    /// Fails if given name is not found even if the config file says otherwise.
+    no_fuzzy: bool,
+    #[arg(short, long)]
+    /// Fails if the location, joined with a relative path after the name, does not exist.
+    must_exist: bool,
+    #[arg(long)]
+    /// Outputs the location of a name as saved without expanding "~" and environment variables.
+    raw: bool,
+    #[arg(long)]
+    /// Shows paths in full length even if the lines become wider than the terminal.
+    no_truncate: bool,
+    #[arg(long, value_enum, value_delimiter = ',')]
+    /// Columns to show in the given order separated by commas like "index,name,path".
+    /// Name and path are shown by default.
+    columns: Option<Vec<Column>>,
+    #[arg(long)]
+    /// Adds the columns branch and dirty for locations within a git work tree.
+    git: bool,
+    #[arg(long)]
+    /// Shows a header row with the names of the columns.
+    header: bool,
+    #[arg(long, value_enum, default_value_t)]
+    /// Style of the lines drawn around and between the cells.
+    border: BorderStyle,
+    #[arg(long, value_delimiter = ',')]
+    /// Aligns columns to the left or right like "index=right,name=left".
+    align: Vec<ColumnAlignment>,
+    #[arg(long, value_enum)]
+    /// Order in which names are listed. Without it the names are listed in the order of creation.
+    /// Numbers given for "--ask-number" refer to this order.
+    sort: Option<SortBy>,
+    #[arg(long)]
+    /// Lists the names in reverse order.
+    reverse: bool,
+    #[arg(long, value_enum, conflicts_with = "ask_number")]
+    /// Outputs the location of a name or the listing as plain text/table or as JSON.
+    /// The JSON output contains the kind of every location like "dir" or "broken_symlink".
+    /// Defaults to "table".
+    format: Option<OutputFormat>,
+    #[arg(long, value_parser = parse_spacing)]
+    /// Number of spaces between columns of the listing without borders. Defaults to 2.
+    spacing: Option<usize>,
+    #[arg(long, value_enum)]
+    /// Makes paths in the listing clickable links in terminals supporting them.
+    /// Defaults to "auto" which only creates links if stdout is a terminal.
+    hyperlinks: Option<HyperlinkMode>,
 }
 
 impl GetParams {
+    /// Options not given via cli are taken from the config file.
+    pub fn apply_config(&mut self, config: &AppConfig) {
+        let (get, table) = (config.get(), config.table());
+        if !self.no_clipboard && self.clipboard.is_none() && get.clipboard.unwrap_or_default() {
+            self.clipboard = Some(ClipboardTarget::default());
+        }
+        if !self.no_fuzzy {
+            self.fuzzy |= get.fuzzy.unwrap_or_default();
+        }
+        // JSON can not be combined with asking for a number.
+        if !self.ask_number {
+            self.format = self.format.or(get.format);
+        }
+        self.sort = self.sort.or(get.sort);
+        self.spacing = self.spacing.or(table.spacing);
+        self.hyperlinks = self.hyperlinks.or(table.hyperlinks);
+        self.clear_after = self.clear_after.or(config.clipboard().clear_after);
+    }
+
     pub fn get_name(&self) -> Option<&str> {
         self.name.as_deref()
     }
     pub fn copy_has_clipboard(&self) -> bool {
-        self.clipboard
+        self.clipboard.is_some()
+    }
+    pub fn clipboard_options(&self) -> ClipboardOptions {
+        ClipboardOptions {
+            clear_after: self
+                .clear_after
+                .map(|seconds| Duration::from_secs(seconds.get())),
+            target: self.clipboard.unwrap_or_default(),
+        }
     }
     pub fn copy_ask_number(&self) -> bool {
         self.ask_number
@@ -84,6 +440,52 @@ impl GetParams {
     pub fn copy_fuzzy(&self) -> bool {
         self.fuzzy
     }
+    pub fn copy_must_exist(&self) -> bool {
+        self.must_exist
+    }
+    pub fn copy_raw(&self) -> bool {
+        self.raw
+    }
+    pub fn copy_no_truncate(&self) -> bool {
+        self.no_truncate
+    }
+    pub fn get_columns(&self) -> Option<&[Column]> {
+        self.columns.as_deref()
+    }
+    pub fn copy_git(&self) -> bool {
+        self.git
+    }
+    pub fn copy_header(&self) -> bool {
+        self.header
+    }
+    pub fn copy_border(&self) -> BorderStyle {
+        self.border
+    }
+    pub fn get_alignments(&self) -> &[ColumnAlignment] {
+        &self.align
+    }
+    pub fn copy_sort(&self) -> Option<SortBy> {
+        self.sort
+    }
+    pub fn copy_reverse(&self) -> bool {
+        self.reverse
+    }
+    pub fn copy_format(&self) -> OutputFormat {
+        self.format.unwrap_or_default()
+    }
+    pub fn copy_spacing(&self) -> Option<usize> {
+        self.spacing
+    }
+    pub fn copy_hyperlinks(&self) -> HyperlinkMode {
+        self.hyperlinks.unwrap_or_default()
+    }
+}
+
+fn parse_spacing(spacing: &str) -> Result<usize, String> {
+    let spacing = spacing
+        .parse()
+        .map_err(|_| "Not a whole number".to_string())?;
+    config::check_spacing(spacing)
 }
 
 #[cfg(test)]
@@ -94,6 +496,6 @@ mod testing {
 
     #[test]
     fn verify_cli() {
-        CliCommands::command().debug_assert();
+        CliArgs::command().debug_assert();
     }
 }
@@ -0,0 +1,204 @@
+use std::error::Error;
+use std::ffi::OsString;
+use std::fmt::Display;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use super::ClipboardTarget;
+use crate::launch;
+use crate::prelude::*;
+
+/// Programs reading the content from stdin in the order they are looked for.
+/// "wl-copy" is only used within a Wayland session.
+const COPY_CANDIDATES: &[(&str, &[&str])] = &[
+    ("wl-copy", &[]),
+    ("xclip", &["-selection", "clipboard"]),
+    ("xsel", &["--clipboard", "--input"]),
+    ("pbcopy", &[]),
+];
+
+/// Programs reading the content for the primary selection from stdin.
+const COPY_PRIMARY_CANDIDATES: &[(&str, &[&str])] = &[
+    ("wl-copy", &["--primary"]),
+    ("xclip", &["-selection", "primary"]),
+    ("xsel", &["--primary", "--input"]),
+];
+
+/// Programs writing the content of the clipboard to stdout in the order they are looked for.
+const PASTE_CANDIDATES: &[(&str, &[&str])] = &[
+    ("wl-paste", &["--no-newline"]),
+    ("xclip", &["-selection", "clipboard", "-out"]),
+    ("xsel", &["--clipboard", "--output"]),
+    ("pbpaste", &[]),
+];
+
+/// Whether a command puts content into the clipboard or reads from it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Purpose {
+    Copy,
+    CopyPrimary,
+    Paste,
+}
+
+impl Purpose {
+    fn candidates(self) -> &'static [(&'static str, &'static [&'static str])] {
+        match self {
+            Purpose::Copy => COPY_CANDIDATES,
+            Purpose::CopyPrimary => COPY_PRIMARY_CANDIDATES,
+            Purpose::Paste => PASTE_CANDIDATES,
+        }
+    }
+}
+
+/// Program with its arguments which reads the content for the clipboard from stdin
+/// or writes the content of the clipboard to stdout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClipboardCommand {
+    program: OsString,
+    args: Vec<String>,
+}
+
+impl Display for ClipboardCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.program.to_string_lossy())?;
+        for arg in &self.args {
+            write!(f, " {}", arg)?;
+        }
+        Ok(())
+    }
+}
+
+/// Pipes the content into the configured command or the first program found for every selection
+/// of the target. Returns the used commands.
+pub fn put_into_clipboard(
+    content: &str,
+    configured: Option<&str>,
+    target: ClipboardTarget,
+) -> Result<Vec<ClipboardCommand>, Box<dyn Error>> {
+    let purposes = if configured.is_some() {
+        if target != ClipboardTarget::Clipboard {
+            warn!(
+                "Configured clipboard command is used as is for the target {}",
+                target.name()
+            );
+        }
+        vec![Purpose::Copy]
+    } else {
+        let mut purposes = Vec::new();
+        if target.includes_primary() {
+            purposes.push(Purpose::CopyPrimary);
+        }
+        if target.includes_clipboard() {
+            purposes.push(Purpose::Copy);
+        }
+        purposes
+    };
+    let mut used = Vec::new();
+    for purpose in purposes {
+        let command = find(configured, purpose)?;
+        pipe_into(content, &command)?;
+        used.push(command);
+    }
+    Ok(used)
+}
+
+fn pipe_into(content: &str, command: &ClipboardCommand) -> Result<(), Box<dyn Error>> {
+    debug!(
+        "Putting content into the clipboard via command \"{}\"",
+        command
+    );
+    let mut child = Command::new(&command.program)
+        .args(&command.args)
+        .stdin(Stdio::piped())
+        // Some of these programs stay in the background and would keep the pipes open.
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|error| format!("Could not start \"{}\": {}", command, error))?;
+    // Dropping stdin after writing closes the pipe so the program knows the content is complete.
+    child
+        .stdin
+        .take()
+        .expect("Stdin is piped")
+        .write_all(content.as_bytes())?;
+    let exit_code = launch::exit_code_of(child.wait()?);
+    if exit_code != 0 {
+        return Err(format!("\"{}\" failed with exit code {}", command, exit_code).into());
+    }
+    Ok(())
+}
+
+/// Output of the configured command or the first program found with the used command.
+pub fn get_from_clipboard(
+    configured: Option<&str>,
+) -> Result<(String, ClipboardCommand), Box<dyn Error>> {
+    let command = find(configured, Purpose::Paste)?;
+    debug!("Reading the clipboard via command \"{}\"", command);
+    let output = Command::new(&command.program)
+        .args(&command.args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .map_err(|error| format!("Could not start \"{}\": {}", command, error))?;
+    let exit_code = launch::exit_code_of(output.status);
+    if exit_code != 0 {
+        return Err(format!("\"{}\" failed with exit code {}", command, exit_code).into());
+    }
+    let content = String::from_utf8(output.stdout)
+        .map_err(|_| "Content of the clipboard is not a valid utf 8 text")?;
+    Ok((content, command))
+}
+
+/// The configured command line is used as is. Otherwise the first program found is used.
+pub fn find(
+    configured: Option<&str>,
+    purpose: Purpose,
+) -> Result<ClipboardCommand, Box<dyn Error>> {
+    if let Some(configured) = configured {
+        let mut words = launch::split_words(configured)?.into_iter();
+        let program = words.next().ok_or(launch::InvalidCommandLine::Empty)?;
+        return Ok(ClipboardCommand {
+            program: program.into(),
+            args: words.collect(),
+        });
+    }
+    let is_wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+    let candidates = purpose.candidates();
+    candidates
+        .iter()
+        .filter(|(program, _)| is_wayland || !program.starts_with("wl-"))
+        .find_map(|(program, args)| {
+            find_in_path(program).map(|found| ClipboardCommand {
+                program: found.into_os_string(),
+                args: args.iter().map(|arg| arg.to_string()).collect(),
+            })
+        })
+        .ok_or_else(|| {
+            let names: Vec<_> = candidates
+                .iter()
+                .map(|(program, _)| format!("\"{}\"", program))
+                .collect();
+            format!("None of the commands {} was found", names.join(", ")).into()
+        })
+}
+
+/// Location of an executable within one of the folders of the environment variable PATH.
+fn find_in_path(program: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .map(|folder| folder.join(program))
+        .find(|candidate| is_executable(candidate))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.with_extension("exe").is_file()
+}
@@ -0,0 +1,280 @@
+mod command;
+mod native;
+mod osc52;
+
+use std::error::Error;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use clap::ValueEnum;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::prelude::*;
+use command::{ClipboardCommand, Purpose};
+
+/// Way of reaching a clipboard.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ClipboardBackend {
+    /// "native" if a display is available otherwise "osc52".
+    /// Falls back to "command" if the native clipboard can not be reached.
+    #[default]
+    Auto,
+    /// Clipboard of the OS. Needs a running X11 or Wayland display on Linux.
+    Native,
+    /// Escape sequence OSC 52 handled by the terminal. Works via SSH and within tmux or screen.
+    Osc52,
+    /// Command from the config file or the first found of "wl-copy", "xclip", "xsel" or "pbcopy".
+    Command,
+}
+
+/// Which selection content is put into.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ClipboardTarget {
+    /// Clipboard pasted via ctrl+v.
+    #[default]
+    Clipboard,
+    /// Primary selection of X11 and Wayland pasted via middle click.
+    Primary,
+    Both,
+}
+
+impl ClipboardTarget {
+    pub fn includes_clipboard(self) -> bool {
+        self != ClipboardTarget::Primary
+    }
+
+    pub fn includes_primary(self) -> bool {
+        self != ClipboardTarget::Clipboard
+    }
+
+    /// Name as given via cli like "primary".
+    pub fn name(self) -> String {
+        self.to_possible_value()
+            .map(|value| value.get_name().to_string())
+            .unwrap_or_default()
+    }
+}
+
+/// Options for putting content into the clipboard.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ClipboardOptions {
+    /// Clears the clipboard after this time if it still contains the content.
+    pub clear_after: Option<Duration>,
+    pub target: ClipboardTarget,
+}
+
+/// How the clipboard is reached by every access of this app.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ClipboardSettings {
+    pub backend: ClipboardBackend,
+    /// Command line reading the content from stdin for the backend "command" like "wl-copy -n".
+    pub command: Option<String>,
+    /// Command line writing the content of the clipboard to stdout like "wl-paste -n".
+    pub paste_command: Option<String>,
+}
+
+/// Clipboard could not be reached or read.
+#[derive(Debug, Error)]
+#[error("{0}")]
+pub struct ClipboardError(String);
+
+impl From<Box<dyn Error>> for ClipboardError {
+    fn from(error: Box<dyn Error>) -> Self {
+        Self(error.to_string())
+    }
+}
+
+impl From<&str> for ClipboardError {
+    fn from(message: &str) -> Self {
+        Self(message.to_string())
+    }
+}
+
+static SETTINGS: OnceLock<ClipboardSettings> = OnceLock::new();
+
+/// Only the first call has an effect.
+pub fn use_settings(settings: ClipboardSettings) {
+    let _ = SETTINGS.set(settings);
+}
+
+fn settings() -> &'static ClipboardSettings {
+    SETTINGS.get_or_init(ClipboardSettings::default)
+}
+
+pub fn put_into_clipboard(content: &str, options: ClipboardOptions) -> Result<(), ClipboardError> {
+    let settings = settings();
+    let backend = resolve_backend(settings.backend);
+    debug!(
+        "Putting content into the clipboard via backend {:?}",
+        backend
+    );
+    let clear_after = options.clear_after.filter(|clear_after| {
+        let supported = matches!(backend, ClipboardBackend::Native | ClipboardBackend::Auto);
+        if !supported {
+            warn!(
+                "Clipboard is not cleared after {} seconds with backend {:?}",
+                clear_after.as_secs(),
+                backend
+            );
+        }
+        supported
+    });
+    let (command, target) = (settings.command.as_deref(), options.target);
+    let via_command = |commands: Vec<ClipboardCommand>| {
+        let commands: Vec<String> = commands
+            .iter()
+            .map(|command| format!("\"{}\"", command))
+            .collect();
+        format!("command {}", commands.join(" and "))
+    };
+    let outcome: Result<String, Box<dyn Error>> = match backend {
+        ClipboardBackend::Native => native::put_into_clipboard(content, target, clear_after)
+            .map(|_| backend_name(ClipboardBackend::Native)),
+        ClipboardBackend::Osc52 => osc52::put_into_clipboard(content, target)
+            .map(|_| backend_name(ClipboardBackend::Osc52)),
+        ClipboardBackend::Command => {
+            command::put_into_clipboard(content, command, target).map(via_command)
+        }
+        ClipboardBackend::Auto => native::put_into_clipboard(content, target, clear_after)
+            .map(|_| backend_name(ClipboardBackend::Native))
+            .or_else(|error| {
+                debug!(
+                    "Native clipboard is not reachable: {}. Falling back to backend {:?}",
+                    error,
+                    ClipboardBackend::Command
+                );
+                // The error of the native clipboard is more telling if no command is found.
+                command::put_into_clipboard(content, command, target)
+                    .map(via_command)
+                    .map_err(|_| error)
+            }),
+    };
+    debug!(
+        "Put content for the target {} via {}",
+        target.name(),
+        outcome?
+    );
+    Ok(())
+}
+
+/// Keeps the clipboard alive if this process was started as the background process for it.
+pub fn execute_as_possible_daemon_clipboard() -> Result<(), ClipboardError> {
+    Ok(native::execute_as_possible_daemon_clipboard()?)
+}
+
+/// Text within the clipboard. The escape sequence OSC 52 can only put content into the clipboard.
+pub fn get_from_clipboard() -> Result<String, ClipboardError> {
+    let settings = settings();
+    let backend = resolve_backend(settings.backend);
+    debug!("Reading the clipboard via backend {:?}", backend);
+    let paste_command = settings.paste_command.as_deref();
+    let via_command = |(content, command): (String, ClipboardCommand)| {
+        (content, format!("command \"{}\"", command))
+    };
+    let via_native = |content| (content, backend_name(ClipboardBackend::Native));
+    let outcome: Result<(String, String), Box<dyn Error>> = match backend {
+        ClipboardBackend::Native => native::get_from_clipboard().map(via_native),
+        // Without a display a command may still reach a clipboard like "pbpaste" via SSH.
+        ClipboardBackend::Osc52 if settings.backend == ClipboardBackend::Auto => {
+            command::get_from_clipboard(paste_command).map(via_command)
+        }
+        ClipboardBackend::Osc52 => {
+            Err("Clipboard can not be read via the escape sequence OSC 52. \
+            Choose another backend via \"--clipboard-backend\""
+                .into())
+        }
+        ClipboardBackend::Command => command::get_from_clipboard(paste_command).map(via_command),
+        ClipboardBackend::Auto => native::get_from_clipboard()
+            .map(via_native)
+            .or_else(|error| {
+                debug!(
+                    "Native clipboard is not reachable: {}. Falling back to backend {:?}",
+                    error,
+                    ClipboardBackend::Command
+                );
+                command::get_from_clipboard(paste_command)
+                    .map(via_command)
+                    .map_err(|_| error)
+            }),
+    };
+    let (content, used) = outcome?;
+    debug!("Read the clipboard via {}", used);
+    Ok(content)
+}
+
+/// Without a display the clipboard of the OS is not reachable, for example via SSH
+/// or within a container. The terminal is asked to set its clipboard instead.
+/// Stays "auto" if the native clipboard is tried first with a fallback to a command.
+fn resolve_backend(backend: ClipboardBackend) -> ClipboardBackend {
+    if backend != ClipboardBackend::Auto {
+        return backend;
+    }
+    let is_set = |name| std::env::var_os(name).is_some_and(|value| !value.is_empty());
+    let without_display = if is_on_linux() {
+        !is_set("DISPLAY") && !is_set("WAYLAND_DISPLAY")
+    } else {
+        // Native clipboard would be the one of the remote computer.
+        is_set("SSH_CONNECTION")
+    };
+    if without_display {
+        ClipboardBackend::Osc52
+    } else {
+        ClipboardBackend::Auto
+    }
+}
+
+/// Findings about the ways of reaching a clipboard as pairs of label and description.
+pub fn diagnose() -> Vec<(&'static str, String)> {
+    let settings = settings();
+    let env_value = |name| {
+        std::env::var(name)
+            .map(|value| format!("{:?}", value))
+            .unwrap_or_else(|_| "not set".to_string())
+    };
+    let used = match resolve_backend(settings.backend) {
+        ClipboardBackend::Auto => "native with fallback to command".to_string(),
+        backend => backend_name(backend),
+    };
+    let find_command = |configured: &Option<String>, purpose| match command::find(
+        configured.as_deref(),
+        purpose,
+    ) {
+        Ok(command) => command.to_string(),
+        Err(error) => error.to_string(),
+    };
+    let native = match native::check_reachable() {
+        Ok(()) => "reachable".to_string(),
+        Err(error) => format!("not reachable: {}", error),
+    };
+    vec![
+        ("Configured backend", backend_name(settings.backend)),
+        ("Used backend", used),
+        ("Native clipboard", native),
+        (
+            "Copy command",
+            find_command(&settings.command, Purpose::Copy),
+        ),
+        (
+            "Paste command",
+            find_command(&settings.paste_command, Purpose::Paste),
+        ),
+        ("Terminal multiplexer", osc52::describe_multiplexer()),
+        ("DISPLAY", env_value("DISPLAY")),
+        ("WAYLAND_DISPLAY", env_value("WAYLAND_DISPLAY")),
+        ("SSH_CONNECTION", env_value("SSH_CONNECTION")),
+    ]
+}
+
+/// Name as given via cli like "osc52".
+fn backend_name(backend: ClipboardBackend) -> String {
+    backend
+        .to_possible_value()
+        .map(|value| value.get_name().to_string())
+        .unwrap_or_default()
+}
+
+fn is_on_linux() -> bool {
+    cfg!(target_os = "linux")
+}
@@ -0,0 +1,278 @@
+use arboard::{ClearExtLinux, Clipboard, GetExtLinux, LinuxClipboardKind, SetExtLinux};
+use clap::ValueEnum;
+use std::ffi::{OsStr, OsString};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use std::{env, error::Error, fs, process};
+
+use super::{is_on_linux, ClipboardTarget};
+use crate::prelude::*;
+use crate::{file_access, paths};
+
+/// Extra long and special naming so it will not be passed by the as argument by accident.
+/// This keyword instructs the app to start a new process for keeping the set clipboard alive
+/// on Linux.
+/// The content is passed via stdin so it does not show up in the list of processes.
+/// The second argument is the target like "primary" and an optional third argument
+/// is the number of seconds after which the clipboard is cleared.
+const DAEMON_KEYWORD: &str = "__INTERNAL_DAEMON_FAV_FOLDER";
+
+/// Keeps the content alive via a background process on Linux.
+pub fn put_into_clipboard(
+    content: &str,
+    target: ClipboardTarget,
+    clear_after: Option<Duration>,
+) -> Result<(), Box<dyn Error>> {
+    if is_on_linux() {
+        // Errors of the daemon are not seen so the clipboard is checked beforehand.
+        check_reachable()?;
+        spawn_daemon_for_clipboard(content, target, clear_after)?;
+    } else {
+        if target.includes_primary() {
+            warn!("There is no primary selection on this OS. Using the clipboard instead");
+        }
+        let mut clipboard = Clipboard::new()?;
+        clipboard.set_text(content)?;
+        if let Some(clear_after) = clear_after {
+            warn!(
+                "Clipboard is not cleared after {} seconds on this OS",
+                clear_after.as_secs()
+            );
+        }
+    }
+    Ok(())
+}
+
+pub fn get_from_clipboard() -> Result<String, Box<dyn Error>> {
+    Ok(Clipboard::new()?.get_text()?)
+}
+
+pub fn check_reachable() -> Result<(), Box<dyn Error>> {
+    Clipboard::new()?;
+    Ok(())
+}
+
+pub fn execute_as_possible_daemon_clipboard() -> Result<(), Box<dyn Error>> {
+    if !is_on_linux() {
+        return Ok(());
+    }
+    // Other arguments like locations of subcommand set do not need to be valid utf 8.
+    let args: Vec<OsString> = env::args_os().collect();
+
+    if (3..=4).contains(&args.len()) && args[1] == DAEMON_KEYWORD {
+        let target = ClipboardTarget::from_str(&daemon_arg(&args[2])?, false)?;
+        let clear_after = match args.get(3) {
+            Some(seconds) => Some(Duration::from_secs(daemon_arg(seconds)?.parse()?)),
+            None => None,
+        };
+        let mut content = String::new();
+        std::io::stdin().read_to_string(&mut content)?;
+        run_as_daemon(&content, target, clear_after)?;
+        std::process::exit(0);
+    }
+
+    Ok(())
+}
+
+fn daemon_arg(arg: &OsStr) -> Result<String, Box<dyn Error>> {
+    arg.to_str().map(str::to_string).ok_or_else(|| {
+        format!(
+            "Argument {:?} for the clipboard daemon is not valid utf 8",
+            arg
+        )
+        .into()
+    })
+}
+
+/// Replaces a previously started daemon and keeps the clipboard alive until other content is
+/// put into it or until the clipboard is cleared.
+fn run_as_daemon(
+    content: &str,
+    target: ClipboardTarget,
+    clear_after: Option<Duration>,
+) -> Result<(), Box<dyn Error>> {
+    let pid_file = paths::get_path_to_clipboard_pid()?;
+    take_over_pid_file(&pid_file)?;
+
+    let outcome = set_and_wait_for_clipboard_as_daemon(content, target, clear_after);
+
+    // A newer daemon may have taken over the pid file in the meantime.
+    let _lock = file_access::lock_file(&pid_file.with_extension("lock"))?;
+    if read_pid(&pid_file) == Some(process::id()) {
+        let _ = fs::remove_file(&pid_file);
+    }
+    outcome
+}
+
+/// Stops the previous daemon and writes the own pid into the pid file.
+/// Locked so two daemons started at the same time do not both keep running.
+fn take_over_pid_file(pid_file: &Path) -> Result<(), Box<dyn Error>> {
+    let _lock = file_access::lock_file(&pid_file.with_extension("lock"))?;
+    stop_previous_daemon(pid_file);
+    fs::write(pid_file, process::id().to_string())?;
+    Ok(())
+}
+
+/// With the target "both" the daemon waits until the clipboard is overwritten.
+/// The primary selection is only kept alive as long as the daemon runs.
+fn set_and_wait_for_clipboard_as_daemon(
+    content: &str,
+    target: ClipboardTarget,
+    clear_after: Option<Duration>,
+) -> Result<(), Box<dyn Error>> {
+    let mut kinds = Vec::new();
+    if target.includes_primary() {
+        kinds.push(LinuxClipboardKind::Primary);
+    }
+    if target.includes_clipboard() {
+        kinds.push(LinuxClipboardKind::Clipboard);
+    }
+    let (waited_for, others) = kinds.split_last().expect("Target includes one selection");
+
+    let mut clip = Clipboard::new()?;
+    for &kind in others {
+        clip.set().clipboard(kind).text(content)?;
+    }
+    let set = clip.set().clipboard(*waited_for);
+    match clear_after {
+        Some(clear_after) => {
+            set.wait_until(Instant::now() + clear_after).text(content)?;
+            for &kind in &kinds {
+                // Content put into the selection by someone else in the meantime is kept.
+                if clip
+                    .get()
+                    .clipboard(kind)
+                    .text()
+                    .is_ok_and(|current| current == content)
+                {
+                    debug!(
+                        "Clearing {:?} after {} seconds",
+                        kind,
+                        clear_after.as_secs()
+                    );
+                    clip.clear_with().clipboard(kind)?;
+                }
+            }
+        }
+        None => set.wait().text(content)?,
+    }
+
+    Ok(())
+}
+
+/// Only a process which is still a clipboard daemon of this app is stopped.
+/// The pid of an exited daemon could have been reused by an unrelated process.
+fn stop_previous_daemon(pid_file: &Path) {
+    let Some(pid) = read_pid(pid_file) else {
+        return;
+    };
+    if pid == process::id() || !is_clipboard_daemon(pid) {
+        return;
+    }
+    debug!("Stopping previous clipboard daemon with pid {}", pid);
+    #[cfg(unix)]
+    {
+        let stopped = libc::pid_t::try_from(pid)
+            // SAFETY: Sending a signal has no effect on the memory of this process.
+            .is_ok_and(|pid| unsafe { libc::kill(pid, libc::SIGTERM) } == 0);
+        if !stopped {
+            warn!(
+                "Could not stop previous clipboard daemon with pid {}.\n Details: {}",
+                pid,
+                std::io::Error::last_os_error()
+            );
+        }
+    }
+}
+
+fn read_pid(pid_file: &Path) -> Option<u32> {
+    fs::read_to_string(pid_file).ok()?.trim().parse().ok()
+}
+
+fn is_clipboard_daemon(pid: u32) -> bool {
+    let cmdline = PathBuf::from(format!("/proc/{}/cmdline", pid));
+    fs::read(cmdline).is_ok_and(|cmdline| {
+        cmdline
+            .split(|&byte| byte == 0)
+            .any(|arg| arg == DAEMON_KEYWORD.as_bytes())
+    })
+}
+
+fn spawn_daemon_for_clipboard(
+    content: &str,
+    target: ClipboardTarget,
+    clear_after: Option<Duration>,
+) -> Result<(), Box<dyn Error>> {
+    let exe_path = env::current_exe()?;
+    let mut command = process::Command::new(exe_path);
+    command.arg(DAEMON_KEYWORD).arg(target.name());
+    if let Some(clear_after) = clear_after {
+        command.arg(clear_after.as_secs().to_string());
+    }
+    let mut daemon = command
+        .stdin(process::Stdio::piped())
+        .stdout(process::Stdio::null())
+        .stderr(process::Stdio::null())
+        .current_dir("/")
+        .spawn()?;
+    // Dropping stdin after writing closes the pipe so the daemon knows the content is complete.
+    daemon
+        .stdin
+        .take()
+        .expect("Stdin is piped")
+        .write_all(content.as_bytes())?;
+
+    Ok(())
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod testing {
+    use super::*;
+    use crate::test_utils::given_test_dir;
+    use std::os::unix::process::ExitStatusExt;
+
+    #[test]
+    fn read_pid_from_pid_file() {
+        let dir = given_test_dir("native_read_pid");
+        let pid_file = dir.join("daemon.pid");
+        assert_eq!(None, read_pid(&pid_file));
+        fs::write(&pid_file, "1234\n").unwrap();
+        assert_eq!(Some(1234), read_pid(&pid_file));
+        fs::write(&pid_file, "not a pid").unwrap();
+        assert_eq!(None, read_pid(&pid_file));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn replace_only_previous_daemon() {
+        let dir = given_test_dir("native_replace_daemon");
+        let pid_file = dir.join("daemon.pid");
+        // The keyword is the name of the shell script so it shows up in the command line.
+        let mut daemon = process::Command::new("sh")
+            .args(["-c", "sleep 10; true", DAEMON_KEYWORD])
+            .spawn()
+            .unwrap();
+        let mut unrelated = process::Command::new("sh")
+            .args(["-c", "sleep 10; true"])
+            .spawn()
+            .unwrap();
+        assert!(is_clipboard_daemon(daemon.id()));
+        assert!(!is_clipboard_daemon(unrelated.id()));
+        assert!(!is_clipboard_daemon(process::id()));
+
+        fs::write(&pid_file, unrelated.id().to_string()).unwrap();
+        take_over_pid_file(&pid_file).unwrap();
+        assert_eq!(Some(process::id()), read_pid(&pid_file));
+        assert_eq!(None, unrelated.try_wait().unwrap());
+
+        fs::write(&pid_file, daemon.id().to_string()).unwrap();
+        take_over_pid_file(&pid_file).unwrap();
+        assert_eq!(Some(process::id()), read_pid(&pid_file));
+        assert_eq!(Some(libc::SIGTERM), daemon.wait().unwrap().signal());
+
+        unrelated.kill().unwrap();
+        unrelated.wait().unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,168 @@
+use std::error::Error;
+use std::io::Write;
+
+use super::ClipboardTarget;
+
+/// Terminal multiplexer which needs the escape sequence wrapped to pass it on to the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Multiplexer {
+    None,
+    /// Needs "set -g allow-passthrough on" since tmux 3.3.
+    Tmux,
+    Screen,
+}
+
+impl Multiplexer {
+    fn detect() -> Self {
+        if std::env::var_os("TMUX").is_some() {
+            Self::Tmux
+        } else if std::env::var_os("STY").is_some() {
+            Self::Screen
+        } else {
+            Self::None
+        }
+    }
+}
+
+/// Name of the multiplexer the escape sequence is wrapped for.
+pub fn describe_multiplexer() -> String {
+    match Multiplexer::detect() {
+        Multiplexer::None => "none",
+        Multiplexer::Tmux => "tmux",
+        Multiplexer::Screen => "screen",
+    }
+    .to_string()
+}
+
+/// Screen drops escape sequences longer than about 768 bytes so they are sent in pieces.
+const SCREEN_CHUNK_LEN: usize = 76;
+
+/// Asks the terminal to put the content into its clipboard.
+/// The terminal needs to support OSC 52 and may need to allow access to the clipboard.
+pub fn put_into_clipboard(content: &str, target: ClipboardTarget) -> Result<(), Box<dyn Error>> {
+    let sequence = wrap_for(Multiplexer::detect(), &osc52_sequence(content, target));
+    let mut terminal = open_terminal()?;
+    terminal.write_all(sequence.as_bytes())?;
+    terminal.flush()?;
+    Ok(())
+}
+
+/// Writes to the controlling terminal so the sequence does not end up in piped stdout.
+#[cfg(unix)]
+fn open_terminal() -> Result<impl Write, Box<dyn Error>> {
+    std::fs::OpenOptions::new()
+        .write(true)
+        .open("/dev/tty")
+        .map_err(|error| {
+            format!(
+                "No terminal for putting content into its clipboard: {}",
+                error
+            )
+            .into()
+        })
+}
+
+#[cfg(not(unix))]
+fn open_terminal() -> Result<impl Write, Box<dyn Error>> {
+    use std::io::IsTerminal;
+    let stdout = std::io::stdout();
+    if stdout.is_terminal() {
+        Ok(stdout)
+    } else {
+        Err("No terminal for putting content into its clipboard".into())
+    }
+}
+
+/// Selection "p" is the primary selection and "c" the clipboard.
+fn osc52_sequence(content: &str, target: ClipboardTarget) -> String {
+    let selections = match target {
+        ClipboardTarget::Clipboard => "c",
+        ClipboardTarget::Primary => "p",
+        ClipboardTarget::Both => "pc",
+    };
+    format!(
+        "\x1b]52;{};{}\x07",
+        selections,
+        encode_base64(content.as_bytes())
+    )
+}
+
+fn wrap_for(multiplexer: Multiplexer, sequence: &str) -> String {
+    match multiplexer {
+        Multiplexer::None => sequence.to_string(),
+        Multiplexer::Tmux => format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b")),
+        Multiplexer::Screen => sequence
+            .as_bytes()
+            .chunks(SCREEN_CHUNK_LEN)
+            .map(|chunk| format!("\x1bP{}\x1b\\", String::from_utf8_lossy(chunk)))
+            .collect(),
+    }
+}
+
+/// Standard base64 with padding as expected by OSC 52.
+fn encode_base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let triple = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |triple, (index, &byte)| {
+                triple | u32::from(byte) << (16 - 8 * index)
+            });
+        for index in 0..4 {
+            if index <= chunk.len() {
+                let sextet = (triple >> (18 - 6 * index)) & 0b11_1111;
+                encoded.push(char::from(ALPHABET[sextet as usize]));
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn encode_as_base64() {
+        fn assert_case(given: &str, expected: &str) {
+            assert_eq!(
+                expected,
+                encode_base64(given.as_bytes()),
+                "Given: {}",
+                given
+            );
+        }
+
+        assert_case("", "");
+        assert_case("f", "Zg==");
+        assert_case("fo", "Zm8=");
+        assert_case("foo", "Zm9v");
+        assert_case(
+            "/home/some_user/Doküments",
+            "L2hvbWUvc29tZV91c2VyL0Rva8O8bWVudHM=",
+        );
+    }
+
+    #[test]
+    fn wrap_sequence_for_multiplexers() {
+        assert_eq!(
+            "\x1b]52;pc;L3RtcA==\x07",
+            osc52_sequence("/tmp", ClipboardTarget::Both)
+        );
+        let sequence = osc52_sequence("/tmp", ClipboardTarget::Clipboard);
+        assert_eq!("\x1b]52;c;L3RtcA==\x07", sequence);
+        assert_eq!(sequence, wrap_for(Multiplexer::None, &sequence));
+        assert_eq!(
+            "\x1bPtmux;\x1b\x1b]52;c;L3RtcA==\x07\x1b\\",
+            wrap_for(Multiplexer::Tmux, &sequence)
+        );
+        assert_eq!(
+            "\x1bP\x1b]52;c;L3RtcA==\x07\x1b\\",
+            wrap_for(Multiplexer::Screen, &sequence)
+        );
+    }
+}
@@ -0,0 +1,267 @@
+use std::fs;
+use std::path::Path;
+
+use toml_edit::{DocumentMut, Item, Value};
+
+use super::{parse, read, ConfigError};
+
+/// Type of the value expected for a key of the config file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ValueKind {
+    Text,
+    Bool,
+    Integer,
+}
+
+impl ValueKind {
+    fn of_key(key: &str) -> Option<Self> {
+        match key {
+            "color" | "data_file" | "get.format" | "get.sort" | "table.hyperlinks" => {
+                Some(Self::Text)
+            }
+            "open.default" | "open.editor" | "open.file_manager" | "open.terminal" => {
+                Some(Self::Text)
+            }
+            "clipboard.backend" | "clipboard.command" | "clipboard.paste_command" => {
+                Some(Self::Text)
+            }
+            "track_usage" | "get.fuzzy" | "get.clipboard" => Some(Self::Bool),
+            "table.spacing" | "clipboard.clear_after" => Some(Self::Integer),
+            // Unknown roles are rejected while validating the changed config.
+            _ if key.starts_with("theme.") => Some(Self::Text),
+            _ => None,
+        }
+    }
+
+    fn parse(self, key: &str, value: &str) -> Result<Value, ConfigError> {
+        let wrong_type = |expected| ConfigError::WrongType {
+            key: key.to_string(),
+            expected,
+            value: value.to_string(),
+        };
+        match self {
+            Self::Text => Ok(value.into()),
+            Self::Bool => value
+                .parse::<bool>()
+                .map(Value::from)
+                .map_err(|_| wrong_type("true or false")),
+            Self::Integer => value
+                .parse::<i64>()
+                .map(Value::from)
+                .map_err(|_| wrong_type("a whole number")),
+        }
+    }
+}
+
+/// Value of a key like "get.sort" as written in the config file.
+pub fn get_value(path: &Path, key: &str) -> Result<String, ConfigError> {
+    ValueKind::of_key(key).ok_or_else(|| ConfigError::UnknownKey(key.to_string()))?;
+    let document = read_document(path)?;
+    let (section, name) = split_key(key);
+    let item = match section {
+        Some(section) => document.get(section).and_then(|table| table.get(name)),
+        None => document.get(name),
+    };
+    item.and_then(Item::as_value)
+        .map(display_value)
+        .ok_or_else(|| ConfigError::NotSet(key.to_string()))
+}
+
+/// Every key set in the config file with its value like `get.sort = "name"`.
+pub fn list_values(path: &Path) -> Result<Vec<String>, ConfigError> {
+    let document = read_document(path)?;
+    let mut lines = Vec::new();
+    for (key, item) in document.iter() {
+        match item {
+            Item::Value(value) => lines.push(format!("{} = {}", key, value.to_string().trim())),
+            Item::Table(table) => {
+                for (name, item) in table.iter() {
+                    if let Some(value) = item.as_value() {
+                        lines.push(format!("{}.{} = {}", key, name, value.to_string().trim()));
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+    Ok(lines)
+}
+
+/// Sets the key to the value while keeping comments and formatting of the config file.
+/// The config file is only changed if it stays valid.
+pub fn set_value(path: &Path, key: &str, value: &str) -> Result<(), ConfigError> {
+    let content = change_value(path, key, value)?.content;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|source| ConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+    }
+    fs::write(path, content).map_err(|source| ConfigError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Describes how [`set_value`] would change the config file without changing it.
+pub fn describe_set_value(path: &Path, key: &str, value: &str) -> Result<String, ConfigError> {
+    let change = change_value(path, key, value)?;
+    Ok(match change.previous {
+        Some(previous) => format!("Would change {} from {} to {}", key, previous, change.value),
+        None => format!("Would set {} to {}", key, change.value),
+    })
+}
+
+/// Validated content of the config file after setting a value.
+struct ValueChange {
+    previous: Option<String>,
+    value: String,
+    content: String,
+}
+
+fn change_value(path: &Path, key: &str, value: &str) -> Result<ValueChange, ConfigError> {
+    let kind = ValueKind::of_key(key).ok_or_else(|| ConfigError::UnknownKey(key.to_string()))?;
+    let value = kind.parse(key, value)?;
+    let shown_value = display_value(&value);
+    let mut document = read_document(path)?;
+    let slot = match split_key(key) {
+        (Some(section), name) => {
+            let table = document
+                .entry(section)
+                .or_insert_with(toml_edit::table)
+                .as_table_mut()
+                .ok_or_else(|| ConfigError::UnknownKey(key.to_string()))?;
+            &mut table[name]
+        }
+        (None, name) => &mut document[name],
+    };
+    let previous = slot.as_value().map(display_value);
+    *slot = Item::Value(value);
+
+    let content = document.to_string();
+    parse(&content, path)?;
+    Ok(ValueChange {
+        previous,
+        value: shown_value,
+        content,
+    })
+}
+
+fn read_document(path: &Path) -> Result<DocumentMut, ConfigError> {
+    read(path)?
+        .unwrap_or_default()
+        .parse()
+        .map_err(|source| ConfigError::NotEditable {
+            path: path.to_path_buf(),
+            source,
+        })
+}
+
+/// Splits "get.sort" into the section "get" and the name "sort".
+fn split_key(key: &str) -> (Option<&str>, &str) {
+    match key.split_once('.') {
+        Some((section, name)) => (Some(section), name),
+        None => (None, key),
+    }
+}
+
+/// Texts are shown without quotes.
+fn display_value(value: &Value) -> String {
+    match value.as_str() {
+        Some(text) => text.to_string(),
+        None => value.to_string().trim().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    use crate::test_utils::given_test_dir;
+    use std::path::PathBuf;
+
+    fn given_config_file(name: &str, content: &str) -> PathBuf {
+        let dir = given_test_dir(&format!("config_{}", name));
+        let path = dir.join("config.toml");
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn set_values_and_keep_comments() {
+        let path = given_config_file("set", "# Chosen by me\ncolor = \"never\"\n");
+
+        set_value(&path, "get.sort", "name").unwrap();
+        set_value(&path, "table.spacing", "3").unwrap();
+        set_value(&path, "get.fuzzy", "true").unwrap();
+        set_value(&path, "color", "always").unwrap();
+
+        let actual = fs::read_to_string(&path).unwrap();
+        assert!(actual.starts_with("# Chosen by me\n"), "Actual: {}", actual);
+        assert_eq!("name", get_value(&path, "get.sort").unwrap());
+        assert_eq!("3", get_value(&path, "table.spacing").unwrap());
+        assert_eq!(
+            vec![
+                "color = \"always\"",
+                "get.sort = \"name\"",
+                "get.fuzzy = true",
+                "table.spacing = 3",
+            ],
+            list_values(&path).unwrap()
+        );
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn reject_invalid_changes() {
+        let path = given_config_file("invalid", "");
+
+        assert!(matches!(
+            set_value(&path, "colour", "never"),
+            Err(ConfigError::UnknownKey(_))
+        ));
+        assert!(matches!(
+            set_value(&path, "get.fuzzy", "yes"),
+            Err(ConfigError::WrongType { .. })
+        ));
+        assert!(matches!(
+            set_value(&path, "table.spacing", "0"),
+            Err(ConfigError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            set_value(&path, "theme.unknown_role", "red"),
+            Err(ConfigError::Invalid { .. })
+        ));
+        assert!(matches!(
+            get_value(&path, "get.sort"),
+            Err(ConfigError::NotSet(_))
+        ));
+        assert_eq!("", fs::read_to_string(&path).unwrap());
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn describe_change_without_writing() {
+        let path = given_config_file("describe", "[get]\nsort = \"name\"\n");
+
+        assert_eq!(
+            "Would change get.sort from name to use-count",
+            describe_set_value(&path, "get.sort", "use-count").unwrap()
+        );
+        assert_eq!(
+            "Would set table.spacing to 3",
+            describe_set_value(&path, "table.spacing", "3").unwrap()
+        );
+        assert!(matches!(
+            describe_set_value(&path, "table.spacing", "0"),
+            Err(ConfigError::InvalidValue { .. })
+        ));
+        assert_eq!(
+            "[get]\nsort = \"name\"\n",
+            fs::read_to_string(&path).unwrap()
+        );
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
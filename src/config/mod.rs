@@ -0,0 +1,324 @@
+pub use edit::{describe_set_value, get_value, list_values, set_value};
+
+mod edit;
+
+use std::fs;
+use std::io::ErrorKind;
+use std::num::NonZeroU64;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::all_favorites::SortBy;
+use crate::app::color_mode::ColorMode;
+use crate::app::theme::ThemeConfig;
+use crate::clipboard::ClipboardBackend;
+use crate::favorite_table::HyperlinkMode;
+use crate::launch;
+use crate::output_format::OutputFormat;
+use crate::paths::{expand_location_from_env, DataFolderError};
+use crate::prelude::*;
+
+/// Allowed range for the number of spaces between columns of a table.
+const SPACING_RANGE: std::ops::RangeInclusive<usize> = 1..=16;
+
+/// Content of the optional config file in the TOML format.
+/// Options given via cli take precedence over the values in the config file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AppConfig {
+    color: Option<ColorMode>,
+    /// Location of the data file instead of the typical app data location of the OS.
+    /// Can contain "~" and environment variables.
+    data_file: Option<String>,
+    /// Remembers when and how often a location was output by get, open and exec.
+    /// Off by default as it changes the data file on every output of a location.
+    track_usage: Option<bool>,
+    #[serde(default)]
+    get: GetConfig,
+    #[serde(default)]
+    table: TableConfig,
+    #[serde(default)]
+    theme: ThemeConfig,
+    #[serde(default)]
+    open: OpenConfig,
+    #[serde(default)]
+    clipboard: ClipboardConfig,
+}
+
+/// Section `[get]` with defaults for the subcommand get.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GetConfig {
+    pub format: Option<OutputFormat>,
+    pub sort: Option<SortBy>,
+    /// Lists all names containing the given name if no name matches exactly.
+    pub fuzzy: Option<bool>,
+    /// Writes the output into the clipboard instead of stdout.
+    pub clipboard: Option<bool>,
+}
+
+/// Section `[table]` with defaults for the listing of favorites.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TableConfig {
+    /// Number of spaces between columns without borders.
+    pub spacing: Option<usize>,
+    /// Makes paths clickable links via "auto", "always" or "never".
+    pub hyperlinks: Option<HyperlinkMode>,
+}
+
+/// Section `[open]` with command lines for the subcommand open.
+/// Every command line can contain the placeholders "{path}", "{dir}" and "{name}".
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OpenConfig {
+    /// Used without "--with" instead of the app registered in the OS.
+    pub default: Option<String>,
+    /// Used instead of the environment variables VISUAL and EDITOR.
+    pub editor: Option<String>,
+    pub file_manager: Option<String>,
+    pub terminal: Option<String>,
+}
+
+/// Section `[clipboard]` with options for putting output into the clipboard.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ClipboardConfig {
+    /// Seconds after which the clipboard is cleared if it still contains the output.
+    pub clear_after: Option<NonZeroU64>,
+    /// How the clipboard is reached via "auto", "native", "osc52" or "command".
+    pub backend: Option<ClipboardBackend>,
+    /// Command line reading the content from stdin for the backend "command" like "wl-copy -n".
+    pub command: Option<String>,
+    /// Command line writing the content of the clipboard to stdout like "wl-paste -n".
+    pub paste_command: Option<String>,
+}
+
+impl AppConfig {
+    pub fn color(&self) -> Option<ColorMode> {
+        self.color
+    }
+
+    /// Location of the data file with expanded "~" and environment variables.
+    pub fn data_file(&self) -> Option<PathBuf> {
+        self.data_file
+            .as_deref()
+            .map(|data_file| PathBuf::from(expand_location_from_env(data_file).into_owned()))
+    }
+
+    pub fn track_usage(&self) -> bool {
+        self.track_usage.unwrap_or_default()
+    }
+
+    pub fn get(&self) -> &GetConfig {
+        &self.get
+    }
+
+    pub fn table(&self) -> &TableConfig {
+        &self.table
+    }
+
+    pub fn theme(&self) -> &ThemeConfig {
+        &self.theme
+    }
+
+    pub fn open(&self) -> &OpenConfig {
+        &self.open
+    }
+
+    pub fn clipboard(&self) -> &ClipboardConfig {
+        &self.clipboard
+    }
+
+    /// Checks values which can not be checked while parsing.
+    /// Returns the key of the first invalid value and why it is invalid.
+    fn validate(&self) -> Result<(), (&'static str, String)> {
+        if let Some(spacing) = self.table.spacing {
+            check_spacing(spacing).map_err(|message| ("table.spacing", message))?;
+        }
+        let open = &self.open;
+        for (key, command_line) in [
+            ("open.default", &open.default),
+            ("open.editor", &open.editor),
+            ("open.file_manager", &open.file_manager),
+            ("open.terminal", &open.terminal),
+            ("clipboard.command", &self.clipboard.command),
+            ("clipboard.paste_command", &self.clipboard.paste_command),
+        ] {
+            if let Some(command_line) = command_line {
+                launch::split_words(command_line).map_err(|error| (key, error.to_string()))?;
+            }
+        }
+        if let Some(data_file) = self.data_file() {
+            if !data_file.is_absolute() {
+                return Err((
+                    "data_file",
+                    format!("{:?} is not an absolute path", data_file),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Checks the number of spaces between columns given via config file or cli.
+pub fn check_spacing(spacing: usize) -> Result<usize, String> {
+    if SPACING_RANGE.contains(&spacing) {
+        Ok(spacing)
+    } else {
+        Err(format!(
+            "{} is not between {} and {}",
+            spacing,
+            SPACING_RANGE.start(),
+            SPACING_RANGE.end()
+        ))
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("{0}")]
+    Locate(#[from] DataFolderError),
+    #[error("Could not read or write config file at {path:?}.\n Details: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Invalid config file at {path:?}.\n{source}")]
+    Invalid {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    #[error("Invalid value for key \"{key}\" in config file at {path:?}: {message}")]
+    InvalidValue {
+        path: PathBuf,
+        key: &'static str,
+        message: String,
+    },
+    #[error("Config file at {path:?} can not be edited.\n{source}")]
+    NotEditable {
+        path: PathBuf,
+        source: toml_edit::TomlError,
+    },
+    #[error("Unknown config key \"{0}\"")]
+    UnknownKey(String),
+    #[error("Key \"{key}\" expects {expected} but got \"{value}\"")]
+    WrongType {
+        key: String,
+        expected: &'static str,
+        value: String,
+    },
+    #[error("Key \"{0}\" is not set in the config file")]
+    NotSet(String),
+}
+
+/// Returns the default config if there is no config file at the given path.
+pub fn load(path: &Path) -> Result<AppConfig, ConfigError> {
+    match read(path)? {
+        Some(content) => parse(&content, path),
+        None => {
+            debug!("No config file at {:?}. Using default config.", path);
+            Ok(AppConfig::default())
+        }
+    }
+}
+
+/// Content of the config file or none if there is no config file.
+fn read(path: &Path) -> Result<Option<String>, ConfigError> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(error) if error.kind() == ErrorKind::NotFound => Ok(None),
+        Err(source) => Err(ConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        }),
+    }
+}
+
+fn parse(content: &str, path: &Path) -> Result<AppConfig, ConfigError> {
+    let config: AppConfig = toml::from_str(content).map_err(|source| ConfigError::Invalid {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    config
+        .validate()
+        .map_err(|(key, message)| ConfigError::InvalidValue {
+            path: path.to_path_buf(),
+            key,
+            message,
+        })?;
+    Ok(config)
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    fn parse_error(content: &str) -> String {
+        parse(content, Path::new("config.toml"))
+            .unwrap_err()
+            .to_string()
+    }
+
+    #[test]
+    fn parse_empty_config() {
+        let actual = parse("", Path::new("config.toml")).unwrap();
+        assert_eq!(&ThemeConfig::default(), actual.theme());
+        assert_eq!(None, actual.get().sort);
+    }
+
+    #[test]
+    fn parse_all_sections() {
+        let actual = parse(
+            r#"
+            color = "never"
+            data_file = "/home/some_user/favorites.json"
+
+            [get]
+            format = "json"
+            sort = "last-used"
+            fuzzy = true
+
+            [table]
+            spacing = 4
+
+            [clipboard]
+            clear_after = 30
+            backend = "osc52"
+            command = "wl-copy -n"
+            "#,
+            Path::new("config.toml"),
+        )
+        .unwrap();
+        assert_eq!(Some(ColorMode::Never), actual.color());
+        assert_eq!(
+            Some(PathBuf::from("/home/some_user/favorites.json")),
+            actual.data_file()
+        );
+        assert_eq!(Some(OutputFormat::Json), actual.get().format);
+        assert_eq!(Some(SortBy::LastUsed), actual.get().sort);
+        assert_eq!(Some(true), actual.get().fuzzy);
+        assert_eq!(None, actual.get().clipboard);
+        assert_eq!(Some(4), actual.table().spacing);
+        assert_eq!(NonZeroU64::new(30), actual.clipboard().clear_after);
+        assert_eq!(Some(ClipboardBackend::Osc52), actual.clipboard().backend);
+        assert_eq!(Some("wl-copy -n".to_string()), actual.clipboard().command);
+    }
+
+    #[test]
+    fn point_at_offending_key() {
+        let actual = parse_error("[colours]\nprompt = \"red\"");
+        assert!(actual.contains("colours"), "Actual: {}", actual);
+
+        let actual = parse_error("[get]\nsort = \"size\"");
+        assert!(actual.contains("sort = \"size\""), "Actual: {}", actual);
+
+        let actual = parse_error("[table]\nspacing = 0");
+        assert!(actual.contains("\"table.spacing\""), "Actual: {}", actual);
+
+        let actual = parse_error("data_file = \"favorites.json\"");
+        assert!(actual.contains("\"data_file\""), "Actual: {}", actual);
+    }
+}
@@ -2,4 +2,9 @@ pub const LOGGING_DEFAULT_THRESHOLD: &str = "info";
 pub const LOG_ENV_NAME: &str = "FAVORITE_FOLDER_LOG";
 pub const TMP_PREFIX: &str = "local_development_data_folder";
 pub const APP_DATA_FILE: &str = "favorites.json";
+pub const APP_CONFIG_FILE: &str = "config.toml";
+pub const BACKUP_FOLDER: &str = "backups";
+/// Older backups of the data file are removed.
+pub const MAX_BACKUPS: usize = 10;
+pub const CLIPBOARD_PID_FILE: &str = "clipboard_daemon.pid";
 pub const APP_NAME: &str = env!("CARGO_PKG_NAME");
@@ -1,51 +1,267 @@
-use crate::all_favorites::AfterInsertion;
+use crate::all_favorites::{AfterInsertion, AllFavorites};
 
-use crate::favorite_folder_record::FavoriteFolderPath;
+use crate::error::AppError;
+use crate::favorite_folder_record::{FavoriteFolderPath, LocationOptions};
+use crate::favorite_location::FavoriteLocation;
 use crate::trimmed_not_empty_text::NonEmptyText;
-use crate::{file_access, AppResult};
-use log::info;
+use crate::{clipboard, file_access, paths, AppResult};
+use log::{info, warn};
 use std::env;
+use std::ffi::OsString;
 use std::path::Path;
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-pub fn reset() -> AppResult {
-    file_access::delete_everything()
+/// Longest path allowed by common file systems in bytes.
+const MAX_PATH_LEN: usize = 4096;
+
+/// Subcommand clean asks for confirmation if it would remove more favorites than this.
+pub const CLEAN_CONFIRM_THRESHOLD: usize = 3;
+
+/// What happens if a location is set for a name which already has another location.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Overwrite {
+    /// Changes it only if the given confirmation agrees.
+    #[default]
+    Ask,
+    Always,
+    /// Fails instead.
+    Never,
 }
 
-pub fn rename_fav(name: &NonEmptyText, new_name: NonEmptyText) -> AppResult {
-    let mut favorites = file_access::get_favorites()?;
+static DRY_RUN: OnceLock<bool> = OnceLock::new();
+static TRACK_USAGE: OnceLock<bool> = OnceLock::new();
 
-    favorites.rename(name, new_name);
+/// Usage of locations is only remembered if enabled.
+/// Only the first call has an effect.
+pub fn use_usage_tracking(track_usage: bool) {
+    let _ = TRACK_USAGE.set(track_usage);
+}
 
-    file_access::save_favorites(favorites).map_err(Box::new)?;
+/// With dry run every change is only printed and nothing is saved.
+/// Only the first call has an effect.
+pub fn use_dry_run(dry_run: bool) {
+    let _ = DRY_RUN.set(dry_run);
+}
+
+fn is_dry_run() -> bool {
+    DRY_RUN.get().copied().unwrap_or_default()
+}
 
+/// Saves the favorites unless this is a dry run.
+fn save(favorites: AllFavorites) -> AppResult {
+    if !is_dry_run() {
+        file_access::save_favorites(favorites)?;
+    }
     Ok(())
 }
 
-pub fn remove_from_fav(name: &NonEmptyText) -> AppResult {
+/// Backs up the data file before a destructive operation.
+fn backup(data_file: &Path) -> AppResult {
+    if let Some(backup) = file_access::backup_data_file(data_file, now())? {
+        eprintln!("Backup of the data file saved at {:?}", backup);
+    }
+    Ok(())
+}
+
+/// Asks via `confirm` unless `yes` is given.
+/// A data file which can not be loaded is removed too as it could only be fixed by hand otherwise.
+pub fn reset(yes: bool, confirm: impl FnOnce(&str) -> AppResult<bool>) -> AppResult {
+    let data_file = paths::get_path_to_data()?;
+    reset_data_file(&data_file, yes, confirm)
+}
+
+/// Locks the data file only after the confirmation so an open prompt does not block
+/// other invocations. Favorites set in the meantime are removed as well.
+fn reset_data_file(
+    data_file: &Path,
+    yes: bool,
+    confirm: impl FnOnce(&str) -> AppResult<bool>,
+) -> AppResult {
+    if !data_file.exists() {
+        println!("Nothing to reset");
+        return Ok(());
+    }
+    let favorites = match file_access::read_favorites(data_file) {
+        Ok(favorites) => Some(favorites),
+        Err(error) => {
+            warn!("{}", error);
+            None
+        }
+    };
+    if is_dry_run() {
+        match &favorites {
+            Some(favorites) => {
+                println!("Would remove all favorites:");
+                print_favorites(favorites.iter());
+            }
+            None => println!("Would remove the unreadable data file"),
+        }
+        return Ok(());
+    }
+    let question = match &favorites {
+        Some(favorites) => format!("Remove all {} favorites?", favorites.as_slice().len()),
+        None => "Remove the unreadable data file?".to_string(),
+    };
+    if !yes && !confirm(&question)? {
+        return Err(AppError::Declined("Nothing was removed".to_string()));
+    }
+    let _lock = file_access::lock_data_file_at(data_file)?;
+    if !data_file.exists() {
+        println!("Nothing to reset");
+        return Ok(());
+    }
+    backup(data_file)?;
+    file_access::delete_data_file(data_file)
+}
+
+/// With `force` a favorite already using the new name is replaced.
+/// It also keeps only the first of several favorites using the old name.
+pub fn rename_fav(name: &NonEmptyText, new_name: NonEmptyText, force: bool) -> AppResult {
+    let _lock = file_access::lock_data_file()?;
+    let mut favorites = if force {
+        file_access::get_favorites_for_repair()?
+    } else {
+        file_access::get_favorites()?
+    };
+
+    let to_print = format!("Would rename {} to {}", name, new_name);
+    favorites.rename(name, new_name, force)?;
+    if is_dry_run() {
+        println!("{}", to_print);
+    }
+
+    save(favorites)
+}
+
+/// Adds the tags to the name or removes them if `remove` is true.
+pub fn tag_fav(name: &NonEmptyText, tags: &[NonEmptyText], remove: bool) -> AppResult {
+    let _lock = file_access::lock_data_file()?;
     let mut favorites = file_access::get_favorites()?;
+    if !favorites.change_tags(name, tags, remove) {
+        return Err(AppError::NotFound(format!(
+            "No favorite with name {} to be tagged",
+            name
+        )));
+    }
+    if is_dry_run() {
+        let (change, preposition) = if remove {
+            ("remove", "from")
+        } else {
+            ("add", "to")
+        };
+        let tags: Vec<&str> = tags.iter().map(|tag| tag.as_str()).collect();
+        println!(
+            "Would {} the tags {} {} name {}",
+            change,
+            tags.join(","),
+            preposition,
+            name
+        );
+    }
+    save(favorites)
+}
+
+/// Removes every favorite under the name, even several ones.
+pub fn remove_from_fav(name: &NonEmptyText) -> AppResult {
+    let _lock = file_access::lock_data_file()?;
+    let mut favorites = file_access::get_favorites_for_repair()?;
+    let found: Vec<&FavoriteFolderPath> = favorites
+        .iter()
+        .filter(|favorite| favorite.get_name() == name.as_str())
+        .collect();
+    if !found.is_empty() && is_dry_run() {
+        println!("Would remove:");
+        print_favorites(found);
+        return Ok(());
+    }
     if favorites.remove_with_name(name) {
-        file_access::save_favorites(favorites)?;
-        Ok(())
+        backup(&paths::get_path_to_data()?)?;
+        save(favorites)
     } else {
-        Err(format!("No favorite with name {} to be deleted", name).into())
+        Err(AppError::NotFound(format!(
+            "No favorite with name {} to be deleted",
+            name
+        )))
     }
 }
 
-pub fn remove_all_non_existing() -> AppResult {
+/// Asks via `confirm` unless `yes` is given if more than [`CLEAN_CONFIRM_THRESHOLD`]
+/// favorites would be removed. A location on an unmounted drive looks dangling too.
+pub fn remove_all_non_existing(
+    yes: bool,
+    confirm: impl FnOnce(&str) -> AppResult<bool>,
+) -> AppResult {
+    let _lock = file_access::lock_data_file()?;
     let mut records = file_access::get_favorites()?;
-    records.clean_all_dangling(matches_on_all_non_existing_paths);
-    file_access::save_favorites(records)?;
-    Ok(())
+    let removed = records.clean_all_dangling(matches_on_all_non_existing_paths);
+    if removed.is_empty() {
+        println!("No favorite with a non-existing location found");
+        return Ok(());
+    }
+    if is_dry_run() {
+        println!("Would remove favorites with a non-existing location:");
+        print_favorites(&removed);
+        return Ok(());
+    }
+    println!("Favorites with a non-existing location:");
+    print_favorites(&removed);
+    if !yes && removed.len() > CLEAN_CONFIRM_THRESHOLD {
+        let question = format!("Remove these {} favorites?", removed.len());
+        if !confirm(&question)? {
+            return Err(AppError::Declined("Nothing was removed".to_string()));
+        }
+    }
+    backup(&paths::get_path_to_data()?)?;
+    save(records)
 }
 
-pub fn set_favorite_data(name: NonEmptyText, path: NonEmptyText) -> AppResult {
-    let mut records = file_access::get_favorites()?;
+fn print_favorites<'a>(favorites: impl IntoIterator<Item = &'a FavoriteFolderPath>) {
+    for favorite in favorites {
+        println!("  {}  {}", favorite.get_name(), favorite.location());
+    }
+}
 
+/// The previous location is printed to stderr if the location of an existing name is changed.
+/// `confirm` is only asked for [`Overwrite::Ask`]. It is asked before locking the data file
+/// so an open prompt does not block other invocations.
+pub fn set_favorite_data(
+    name: NonEmptyText,
+    path: OsString,
+    options: LocationOptions,
+    overwrite: Overwrite,
+    confirm: impl FnOnce(&str) -> AppResult<bool>,
+) -> AppResult {
     info!(
-        "About to use add or change name {} with path {}",
+        "About to use add or change name {} with path {:?}",
         name, path
     );
-    let new_favorite = FavoriteFolderPath::new(name, path);
+    let cwd = env::current_dir()?;
+    let location = FavoriteLocation::new(path)?;
+    let new_favorite =
+        FavoriteFolderPath::new_validated(name, location, &cwd, options)?.with_created(now());
+    // Reading without the lock is fine as the data file is replaced at once when saved.
+    let previous = previous_location(&file_access::get_favorites()?, &new_favorite)?;
+    if is_dry_run() {
+        println!(
+            "{}",
+            describe_dry_set(&new_favorite, previous.as_ref(), overwrite)?
+        );
+        return Ok(());
+    }
+    if let Some(previous) = &previous {
+        may_change_location(&new_favorite, previous, overwrite, confirm)?;
+    }
+
+    let _lock = file_access::lock_data_file()?;
+    let mut records = file_access::get_favorites()?;
+    if overwrite != Overwrite::Always && previous_location(&records, &new_favorite)? != previous {
+        return Err(AppError::Other(format!(
+            "Location of name {} was changed by another invocation in the meantime. \
+            Nothing was changed",
+            new_favorite.get_name()
+        )));
+    }
     match records.insert(new_favorite) {
         AfterInsertion::Changed => info!("Changed: A new path was set for the name",),
         AfterInsertion::Added => {
@@ -53,22 +269,164 @@ pub fn set_favorite_data(name: NonEmptyText, path: NonEmptyText) -> AppResult {
         }
     }
 
-    file_access::save_favorites(records)?;
+    save(records)?;
+    if let Some(previous) = previous {
+        eprintln!("Previous location was {}", previous);
+    }
+
+    Ok(())
+}
+
+/// Location under the name of the new favorite if it differs from the new one.
+fn previous_location(
+    records: &AllFavorites,
+    new_favorite: &FavoriteFolderPath,
+) -> AppResult<Option<FavoriteLocation>> {
+    Ok(records
+        .get(new_favorite.get_name().try_into()?)
+        .map(|previous| previous.location().clone())
+        .filter(|previous| previous != new_favorite.location()))
+}
+
+/// Fails like a real change for [`Overwrite::Never`] but never asks for confirmation
+/// as nothing is changed.
+fn describe_dry_set(
+    new_favorite: &FavoriteFolderPath,
+    previous: Option<&FavoriteLocation>,
+    overwrite: Overwrite,
+) -> AppResult<String> {
+    let name = new_favorite.get_name();
+    match previous {
+        Some(previous) => {
+            may_change_location(new_favorite, previous, overwrite, |_| Ok(true))?;
+            Ok(format!(
+                "Would change location of name {} from {} to {}",
+                name,
+                previous,
+                new_favorite.location()
+            ))
+        }
+        None => Ok(format!(
+            "Would set name {} to location {}",
+            name,
+            new_favorite.location()
+        )),
+    }
+}
+
+fn may_change_location(
+    new_favorite: &FavoriteFolderPath,
+    previous: &FavoriteLocation,
+    overwrite: Overwrite,
+    confirm: impl FnOnce(&str) -> AppResult<bool>,
+) -> AppResult {
+    let name = new_favorite.get_name();
+    match overwrite {
+        Overwrite::Always => Ok(()),
+        Overwrite::Never => Err(AppError::InvalidInput(format!(
+            "Name {} is already used for location {}. Remove \"--no-clobber\" to change it",
+            name, previous
+        ))),
+        Overwrite::Ask => {
+            let question = format!(
+                "Change location of name {}\n  from {}\n  to   {}\n",
+                name,
+                previous,
+                new_favorite.location()
+            );
+            if confirm(&question)? {
+                Ok(())
+            } else {
+                Err(AppError::Declined(format!(
+                    "Location of name {} was not changed",
+                    name
+                )))
+            }
+        }
+    }
+}
 
+/// Remembers that the location under the name was output right now
+/// if usage tracking is enabled.
+pub fn mark_as_used(name: &str) -> AppResult {
+    if !TRACK_USAGE.get().copied().unwrap_or_default() {
+        return Ok(());
+    }
+    let _lock = file_access::lock_data_file()?;
+    let mut favorites = file_access::get_favorites()?;
+    if favorites.mark_as_used(name, now()) {
+        save(favorites)?;
+    }
     Ok(())
 }
 
-pub fn set_label_to_cwd(name: NonEmptyText) -> AppResult {
+pub fn set_label_to_cwd(
+    name: NonEmptyText,
+    overwrite: Overwrite,
+    confirm: impl FnOnce(&str) -> AppResult<bool>,
+) -> AppResult {
     let cwd = env::current_dir()?;
-    let cwd_str = cwd
-        .to_str()
-        .ok_or("Could not get working directory as new path value")?
-        .try_into()?;
 
-    set_favorite_data(name, cwd_str)?;
+    set_favorite_data(
+        name,
+        cwd.into_os_string(),
+        LocationOptions::default(),
+        overwrite,
+        confirm,
+    )?;
     Ok(())
 }
 
+/// Creates or changes the name with the location taken from the clipboard.
+/// Text which does not look like a single path is refused unless `force` is given.
+pub fn set_from_clipboard(
+    name: NonEmptyText,
+    options: LocationOptions,
+    force: bool,
+    overwrite: Overwrite,
+    confirm: impl FnOnce(&str) -> AppResult<bool>,
+) -> AppResult {
+    let content = clipboard::get_from_clipboard()?;
+    let location = location_from_clipboard(&content, force)?;
+    info!("Taking location {:?} from the clipboard", location);
+    set_favorite_data(name, location.into(), options, overwrite, confirm)
+}
+
+fn location_from_clipboard(content: &str, force: bool) -> AppResult<String> {
+    let location = NonEmptyText::new(content).map_err(|_| {
+        AppError::InvalidInput("Clipboard contains no text to be used as location".to_string())
+    })?;
+    if !force {
+        check_looks_like_path(&location).map_err(|reason| {
+            AppError::InvalidInput(format!("{}. Use \"--force\" to take it anyway", reason))
+        })?;
+    }
+    Ok(location.into())
+}
+
+/// Catches text which was put into the clipboard for something else than a path.
+fn check_looks_like_path(text: &str) -> Result<(), &'static str> {
+    if text.lines().nth(1).is_some() {
+        Err("Text in the clipboard spans several lines")
+    } else if text.contains("://") {
+        Err("Text in the clipboard looks like an url instead of a path")
+    } else if text.chars().any(char::is_control) {
+        Err("Text in the clipboard contains control characters")
+    } else if text.len() > MAX_PATH_LEN {
+        Err("Text in the clipboard is too long for a path")
+    } else {
+        Ok(())
+    }
+}
+
+/// Seconds since the unix epoch.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_secs())
+        .unwrap_or_default()
+}
+
 fn matches_on_all_non_existing_paths(path: &Path) -> bool {
     match path.try_exists() {
         Ok(true) => false,
@@ -87,3 +445,122 @@ fn matches_on_all_non_existing_paths(path: &Path) -> bool {
         }
     }
 }
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    use crate::test_utils::given_test_dir;
+    use std::fs;
+
+    #[test]
+    fn reset_without_data_file() {
+        let dir = given_test_dir("data_access_reset_missing");
+        let never_asked = |_: &str| -> AppResult<bool> { panic!("Should not ask") };
+
+        reset_data_file(&dir.join("favorites.json"), false, never_asked).unwrap();
+        assert!(!dir.join(crate::constants::BACKUP_FOLDER).exists());
+    }
+
+    #[test]
+    fn reset_unparsable_data_file() {
+        let dir = given_test_dir("data_access_reset");
+        let data_file = dir.join("favorites.json");
+        fs::write(&data_file, "[{\"name\": ").unwrap();
+
+        let mut asked = String::new();
+        reset_data_file(&data_file, false, |question| {
+            asked = question.to_string();
+            Ok(true)
+        })
+        .unwrap();
+        assert_eq!("Remove the unreadable data file?", asked);
+        assert!(!data_file.exists());
+        let backups: Vec<String> = fs::read_dir(dir.join(crate::constants::BACKUP_FOLDER))
+            .unwrap()
+            .map(|entry| fs::read_to_string(entry.unwrap().path()).unwrap())
+            .collect();
+        assert_eq!(vec!["[{\"name\": "], backups);
+    }
+
+    #[test]
+    fn change_location_only_if_allowed() {
+        let new_favorite = FavoriteFolderPath::new(
+            NonEmptyText::unwrap("docs"),
+            FavoriteLocation::unwrap("/home/some_user/Documents"),
+        );
+        let previous = FavoriteLocation::unwrap("/home/some_user/Downloads");
+        let never_asked = |_: &str| -> AppResult<bool> { panic!("Should not ask") };
+
+        assert!(
+            may_change_location(&new_favorite, &previous, Overwrite::Always, never_asked).is_ok()
+        );
+        let actual = may_change_location(&new_favorite, &previous, Overwrite::Never, never_asked)
+            .unwrap_err();
+        assert_eq!(4, actual.exit_code());
+        assert!(actual.to_string().contains("--no-clobber"), "{}", actual);
+
+        let mut asked = String::new();
+        assert!(
+            may_change_location(&new_favorite, &previous, Overwrite::Ask, |question| {
+                asked = question.to_string();
+                Ok(true)
+            })
+            .is_ok()
+        );
+        assert!(asked.contains("/home/some_user/Downloads"), "{}", asked);
+        let actual = may_change_location(&new_favorite, &previous, Overwrite::Ask, |_| Ok(false))
+            .unwrap_err();
+        assert_eq!(10, actual.exit_code());
+    }
+
+    #[test]
+    fn describe_dry_set_without_asking() {
+        let new_favorite = FavoriteFolderPath::new(
+            NonEmptyText::unwrap("docs"),
+            FavoriteLocation::unwrap("/home/some_user/Documents"),
+        );
+        let previous = FavoriteLocation::unwrap("/home/some_user/Downloads");
+
+        assert_eq!(
+            "Would set name docs to location /home/some_user/Documents",
+            describe_dry_set(&new_favorite, None, Overwrite::Never).unwrap()
+        );
+        assert_eq!(
+            "Would change location of name docs from /home/some_user/Downloads \
+            to /home/some_user/Documents",
+            describe_dry_set(&new_favorite, Some(&previous), Overwrite::Ask).unwrap()
+        );
+        let actual =
+            describe_dry_set(&new_favorite, Some(&previous), Overwrite::Never).unwrap_err();
+        assert_eq!(4, actual.exit_code());
+    }
+
+    #[test]
+    fn take_only_single_paths_from_clipboard() {
+        assert_eq!(
+            "/home/some_user/Documents",
+            location_from_clipboard("  /home/some_user/Documents\n", false).unwrap()
+        );
+        assert_eq!(
+            "~/my notes",
+            location_from_clipboard("~/my notes", false).unwrap()
+        );
+        for refused in [
+            "/home/a\n/home/b",
+            "https://example.com/home",
+            "/home/\x1b[31mred",
+            " \n ",
+        ] {
+            assert!(
+                location_from_clipboard(refused, false).is_err(),
+                "Given: {:?}",
+                refused
+            );
+        }
+        assert_eq!(
+            "/home/a\n/home/b",
+            location_from_clipboard("/home/a\n/home/b", true).unwrap()
+        );
+        assert!(location_from_clipboard(" \n ", true).is_err());
+    }
+}
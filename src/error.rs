@@ -0,0 +1,170 @@
+use clap::ValueEnum;
+use serde_json::json;
+use thiserror::Error;
+
+use crate::all_favorites::RenameError;
+use crate::clipboard::ClipboardError;
+use crate::config::ConfigError;
+use crate::favorite_folder_record::InvalidFavoriteFields;
+use crate::file_access::DataIoError;
+use crate::launch::InvalidCommandLine;
+use crate::paths::DataFolderError;
+use crate::trimmed_not_empty_text::NotEmptyTextError;
+
+/// Every error which ends this app. Each category has its own exit code for scripts.
+///
+/// | Exit code | Kind            | Cause                                                 |
+/// |-----------|-----------------|-------------------------------------------------------|
+/// | 1         | `other`         | Any other error like a failed file operation          |
+/// | 2         | -               | Invalid arguments, reported by the cli parser         |
+/// | 3         | `not_found`     | Name or location does not exist                       |
+/// | 4         | `invalid_input` | Invalid name, location or answer                      |
+/// | 5         | `data_file`     | Data file could not be read, written or parsed        |
+/// | 6         | `data_folder`   | Data or config folder could not be located            |
+/// | 7         | `config`        | Config file is invalid or can not be changed          |
+/// | 8         | `clipboard`     | Clipboard could not be reached                        |
+/// | 9         | `launch`        | Opener or command is missing, did not start or failed |
+/// | 10        | `declined`      | Change was not confirmed when asked                   |
+#[derive(Debug, Error)]
+pub enum AppError {
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{0}")]
+    InvalidInput(String),
+    #[error(transparent)]
+    EmptyText(#[from] NotEmptyTextError),
+    #[error(transparent)]
+    InvalidFavorite(#[from] InvalidFavoriteFields),
+    #[error(transparent)]
+    InvalidCommandLine(#[from] InvalidCommandLine),
+    #[error(transparent)]
+    DataFile(DataIoError),
+    #[error(transparent)]
+    DataFolder(#[from] DataFolderError),
+    /// Boxed as the config error is much larger than the other variants.
+    #[error(transparent)]
+    Config(Box<ConfigError>),
+    #[error(transparent)]
+    Clipboard(#[from] ClipboardError),
+    #[error("{0}")]
+    Launch(String),
+    #[error("{0}")]
+    Declined(String),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("{0}")]
+    Other(String),
+}
+
+/// How an error is printed to stderr before exiting.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ErrorFormat {
+    /// Red message for humans.
+    #[default]
+    Text,
+    /// Object like {"error": {"kind": "not_found", "exit_code": 3, "message": "..."}}.
+    Json,
+}
+
+impl AppError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            AppError::Io(_) | AppError::Other(_) => 1,
+            AppError::NotFound(_) => 3,
+            AppError::InvalidInput(_)
+            | AppError::EmptyText(_)
+            | AppError::InvalidFavorite(_)
+            | AppError::InvalidCommandLine(_) => 4,
+            AppError::DataFile(_) => 5,
+            AppError::DataFolder(_) => 6,
+            AppError::Config(_) => 7,
+            AppError::Clipboard(_) => 8,
+            AppError::Launch(_) => 9,
+            AppError::Declined(_) => 10,
+        }
+    }
+
+    /// Name of the category within the JSON output.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AppError::Io(_) | AppError::Other(_) => "other",
+            AppError::NotFound(_) => "not_found",
+            AppError::InvalidInput(_)
+            | AppError::EmptyText(_)
+            | AppError::InvalidFavorite(_)
+            | AppError::InvalidCommandLine(_) => "invalid_input",
+            AppError::DataFile(_) => "data_file",
+            AppError::DataFolder(_) => "data_folder",
+            AppError::Config(_) => "config",
+            AppError::Clipboard(_) => "clipboard",
+            AppError::Launch(_) => "launch",
+            AppError::Declined(_) => "declined",
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        json!({
+            "error": {
+                "kind": self.kind(),
+                "exit_code": self.exit_code(),
+                "message": self.to_string().trim(),
+            }
+        })
+        .to_string()
+    }
+}
+
+impl From<ConfigError> for AppError {
+    fn from(error: ConfigError) -> Self {
+        AppError::Config(Box::new(error))
+    }
+}
+
+impl From<DataIoError> for AppError {
+    fn from(error: DataIoError) -> Self {
+        match error {
+            DataIoError::DataDir(error) => AppError::DataFolder(error),
+            error => AppError::DataFile(error),
+        }
+    }
+}
+
+impl From<RenameError> for AppError {
+    fn from(error: RenameError) -> Self {
+        match error {
+            RenameError::NotFound(_) => AppError::NotFound(error.to_string()),
+            RenameError::NameTaken(_) => AppError::InvalidInput(error.to_string()),
+        }
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(error: serde_json::Error) -> Self {
+        AppError::Other(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn report_kind_and_exit_code_as_json() {
+        let actual = AppError::NotFound("No path found for the name: a\"b".to_string()).to_json();
+        assert_eq!(
+            r#"{"error":{"exit_code":3,"kind":"not_found","message":"No path found for the name: a\"b"}}"#,
+            actual
+        );
+        assert_eq!(
+            8,
+            AppError::from(ClipboardError::from("X11 unreachable")).exit_code()
+        );
+        let data_folder = AppError::from(DataIoError::DataDir(DataFolderError::CouldNotLocate));
+        assert_eq!(
+            (6, "data_folder"),
+            (data_folder.exit_code(), data_folder.kind())
+        );
+        let launch = AppError::Launch("No editor found".to_string());
+        assert_eq!((9, "launch"), (launch.exit_code(), launch.kind()));
+    }
+}
@@ -1,6 +1,11 @@
+use log::warn;
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::borrow::Cow;
+use std::ffi::OsStr;
+use std::path::{Component, Path, PathBuf};
 
+use crate::favorite_location::FavoriteLocation;
+use crate::location_kind::LocationKind;
 use crate::trimmed_not_empty_text::{NonEmptyText, NotEmptyTextError};
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -9,36 +14,201 @@ use crate::trimmed_not_empty_text::{NonEmptyText, NotEmptyTextError};
 /// That path does need to point to existing location.
 pub struct FavoriteFolderPath {
     name: NonEmptyText,
-    location: NonEmptyText,
+    location: FavoriteLocation,
+    /// Seconds since the unix epoch when the name was created.
+    /// Not known for favorites created by older versions of this app.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    created: Option<u64>,
+    /// Seconds since the unix epoch when the location was output the last time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_used: Option<u64>,
+    /// How often the location was output.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    use_count: u64,
+    /// Sorted labels for selecting several favorites at once like "work".
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<NonEmptyText>,
+}
+
+fn is_zero(number: &u64) -> bool {
+    *number == 0
+}
+
+/// Controls how strict a location given by the user is validated.
+/// See [`FavoriteFolderPath::new_validated`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LocationOptions {
+    /// Resolves symbolic links and stores the canonical form of the location.
+    pub canonicalize: bool,
+    /// Fails if the location does not exist instead of only warning about it.
+    pub must_exist: bool,
 }
 
 impl FavoriteFolderPath {
+    /// Takes name and location as they are without any validation.
+    /// Use [`FavoriteFolderPath::new_validated`] for locations given by the user.
+    pub fn new(name: NonEmptyText, location: FavoriteLocation) -> Self {
+        Self {
+            name,
+            location,
+            created: None,
+            last_used: None,
+            use_count: 0,
+            tags: Vec::new(),
+        }
+    }
+
+    pub fn with_created(self, created: u64) -> Self {
+        Self {
+            created: Some(created),
+            ..self
+        }
+    }
+
+    /// Creates a favorite from a location given by the user.
+    /// A relative location is resolved against `cwd` and "." or ".." are removed lexically.
+    /// A location with "~" or environment variables is kept as given
+    /// so it is expanded anew every time it is used.
+    /// A warning is logged if the location does not exist.
+    ///
     /// # Errors
     ///
-    /// - If name or path are empty or white spaces.
-    /// - If path can not be resolved to a valid utf 8 string.
-    /// - If a relative path can not be resolved to an absolute one.
-    pub fn new(name: NonEmptyText, location: NonEmptyText) -> Self {
-        Self { name, location }
+    /// - If the location does not exist and `must_exist` is set.
+    /// - If the location can not be canonicalized and `canonicalize` is set.
+    pub fn new_validated(
+        name: NonEmptyText,
+        location: FavoriteLocation,
+        cwd: &Path,
+        options: LocationOptions,
+    ) -> Result<Self, InvalidFavoriteFields> {
+        // Otherwise the unexpanded variable would be taken as a relative path.
+        if let Some(var) = location.unset_var() {
+            return Err(InvalidFavoriteFields::UnsetVariable(var));
+        }
+        let expanded = location.expanded();
+        let is_expandable = expanded != location.as_os_str();
+        let absolute = normalize_lexically(&cwd.join(&expanded));
+
+        if !absolute.exists() {
+            if options.must_exist {
+                return Err(InvalidFavoriteFields::NotExisting(absolute));
+            }
+            warn!("Location {:?} does not exist", absolute);
+        }
+
+        let to_store = if options.canonicalize {
+            let canonical = std::fs::canonicalize(&absolute).map_err(|source| {
+                InvalidFavoriteFields::CouldNotCanonicalize {
+                    path: absolute.clone(),
+                    source,
+                }
+            })?;
+            FavoriteLocation::new(canonical)?
+        } else if is_expandable {
+            location
+        } else {
+            FavoriteLocation::new(absolute)?
+        };
+
+        Ok(Self::new(name, to_store))
     }
 
     pub fn set_name(&mut self, new_name: NonEmptyText) {
         self.name = new_name;
     }
 
+    /// Takes over when `other` was created and used and its tags.
+    /// Used if the location of an existing name is changed.
+    pub fn keep_usage_of(&mut self, other: &FavoriteFolderPath) {
+        self.created = other.created.or(self.created);
+        self.last_used = other.last_used;
+        self.use_count = other.use_count;
+        self.tags = other.tags.clone();
+    }
+
+    pub fn tags(&self) -> &[NonEmptyText] {
+        &self.tags
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|own| own.as_str() == tag)
+    }
+
+    /// Tags which are already given are ignored.
+    pub fn add_tags(&mut self, tags: &[NonEmptyText]) {
+        self.tags.extend_from_slice(tags);
+        self.tags.sort();
+        self.tags.dedup();
+    }
+
+    /// Tags which are not given are ignored.
+    pub fn remove_tags(&mut self, tags: &[NonEmptyText]) {
+        self.tags.retain(|own| !tags.contains(own));
+    }
+
+    pub fn mark_as_used(&mut self, now: u64) {
+        self.last_used = Some(now);
+        self.use_count += 1;
+    }
+
+    pub fn created(&self) -> Option<u64> {
+        self.created
+    }
+
+    pub fn last_used(&self) -> Option<u64> {
+        self.last_used
+    }
+
+    pub fn use_count(&self) -> u64 {
+        self.use_count
+    }
+
     pub fn get_name(&self) -> &str {
         &self.name
     }
 
-    pub fn path_str(&self) -> &str {
+    /// Location as saved by the user without any expansion.
+    pub fn location(&self) -> &FavoriteLocation {
         &self.location
     }
-    pub fn path(&self) -> &Path {
-        Path::new(self.location.as_str())
+
+    /// Location with `~`, `$VAR` and `${VAR:-default}` expanded.
+    /// See [`crate::paths::expand_location`] for details.
+    pub fn expanded_location(&self) -> Cow<'_, OsStr> {
+        self.location.expanded()
     }
+
+    pub fn path(&self) -> PathBuf {
+        PathBuf::from(self.expanded_location().into_owned())
+    }
+
     pub fn does_exit(&self) -> bool {
-        Path::new(self.location.as_str()).exists()
+        self.path().exists()
     }
+
+    pub fn kind(&self) -> LocationKind {
+        LocationKind::classify(&self.path())
+    }
+}
+
+/// Removes "." and resolves ".." without accessing the file system.
+/// A ".." directly after the root is dropped like a shell does.
+pub fn normalize_lexically(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match normalized.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    normalized.pop();
+                }
+                Some(Component::RootDir | Component::Prefix(_)) => {}
+                _ => normalized.push(component.as_os_str()),
+            },
+            other => normalized.push(other.as_os_str()),
+        }
+    }
+    normalized
 }
 
 /// Error to describe the cause of failing to create a struct [`FavoriteFolderPath`]
@@ -51,4 +221,105 @@ pub enum InvalidFavoriteFields {
     EmptyPath,
     #[error("Path of favorite folder is not a valid utf 8 text")]
     InvalidUtf8PathStr,
+    #[error("Variable {0} within the path of favorite folder is not set")]
+    UnsetVariable(String),
+    #[error("Path of favorite folder ({0:?}) does not exist")]
+    NotExisting(PathBuf),
+    #[error("Path of favorite folder ({path:?}) could not be canonicalized: {source}")]
+    CouldNotCanonicalize {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn normalize_path_lexically() {
+        fn assert_case(given: &str, expected: &str) {
+            let actual = normalize_lexically(Path::new(given));
+            assert_eq!(Path::new(expected), actual, "Given: {}", given);
+        }
+
+        assert_case("/home/some_user/Code", "/home/some_user/Code");
+        assert_case("/home/some_user/./Code", "/home/some_user/Code");
+        assert_case(
+            "/home/some_user/Code/../Documents",
+            "/home/some_user/Documents",
+        );
+        assert_case("/home/../../Code", "/Code");
+        assert_case("../Code/./rust", "../Code/rust");
+        assert_case("Code/../../rust", "../rust");
+    }
+
+    #[test]
+    fn resolve_relative_location_against_cwd() {
+        fn assert_case(given: &str, expected: &str) {
+            let actual = FavoriteFolderPath::new_validated(
+                NonEmptyText::unwrap("name"),
+                FavoriteLocation::unwrap(given),
+                Path::new("/home/some_user/Code"),
+                LocationOptions::default(),
+            )
+            .unwrap();
+            assert_eq!(expected, actual.location().as_os_str(), "Given: {}", given);
+        }
+
+        assert_case("rust", "/home/some_user/Code/rust");
+        assert_case("./rust/../python", "/home/some_user/Code/python");
+        assert_case("..", "/home/some_user");
+        assert_case("/opt/./tools", "/opt/tools");
+        assert_case("~/Code", "~/Code");
+        assert_case("${HOME}/Code", "${HOME}/Code");
+    }
+
+    #[test]
+    fn fail_on_unset_variable_in_location() {
+        let actual = FavoriteFolderPath::new_validated(
+            NonEmptyText::unwrap("name"),
+            FavoriteLocation::unwrap("$FAV_FOLDER_NOT_SET_AT_ALL/Code"),
+            Path::new("/home/some_user"),
+            LocationOptions::default(),
+        );
+        assert!(matches!(
+            actual,
+            Err(InvalidFavoriteFields::UnsetVariable(var)) if var == "FAV_FOLDER_NOT_SET_AT_ALL"
+        ));
+    }
+
+    #[test]
+    fn fail_on_non_existing_location_if_must_exist() {
+        let actual = FavoriteFolderPath::new_validated(
+            NonEmptyText::unwrap("name"),
+            FavoriteLocation::unwrap("fav_folder_not_there/at_all"),
+            Path::new("/"),
+            LocationOptions {
+                must_exist: true,
+                ..Default::default()
+            },
+        );
+        assert!(matches!(
+            actual,
+            Err(InvalidFavoriteFields::NotExisting(path)) if path == Path::new("/fav_folder_not_there/at_all")
+        ));
+    }
+
+    #[test]
+    fn canonicalize_existing_location() {
+        let cwd = std::env::temp_dir();
+        let expected = std::fs::canonicalize(&cwd).unwrap();
+        let actual = FavoriteFolderPath::new_validated(
+            NonEmptyText::unwrap("name"),
+            FavoriteLocation::unwrap("."),
+            &cwd,
+            LocationOptions {
+                canonicalize: true,
+                must_exist: true,
+            },
+        )
+        .unwrap();
+        assert_eq!(expected, Path::new(actual.location().as_os_str()));
+    }
 }
@@ -0,0 +1,265 @@
+use std::borrow::Cow;
+use std::ffi::{OsStr, OsString};
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use crate::favorite_folder_record::InvalidFavoriteFields;
+use crate::paths::{expand_location_from_env, find_unset_var};
+
+/// Not empty location of a favorite which can be any path of the OS.
+/// On Unix this includes paths which are not valid utf 8.
+///
+/// A valid utf 8 location is saved as plain text in the JSON file.
+/// Other locations are saved as `{"escaped": "..."}` where every byte which is not valid
+/// utf 8 is written as `\xNN` and a backslash as `\\`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct FavoriteLocation(OsString);
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum LocationRepr<'a> {
+    Text(Cow<'a, str>),
+    Escaped { escaped: String },
+}
+
+impl FavoriteLocation {
+    /// Surrounding whitespaces are removed if the location is valid utf 8.
+    ///
+    /// # Errors
+    ///
+    /// - If location is empty or only whitespaces.
+    /// - If location is not valid utf 8 on an OS other than Unix.
+    pub fn new(location: impl Into<OsString>) -> Result<Self, InvalidFavoriteFields> {
+        let location: OsString = location.into();
+        let location = match location.to_str() {
+            Some(text) => OsString::from(text.trim()),
+            None if cfg!(unix) => location,
+            None => return Err(InvalidFavoriteFields::InvalidUtf8PathStr),
+        };
+
+        if location.is_empty() {
+            Err(InvalidFavoriteFields::EmptyPath)
+        } else {
+            Ok(Self(location))
+        }
+    }
+
+    #[cfg(test)]
+    pub fn unwrap(location: impl Into<OsString>) -> Self {
+        Self::new(location).unwrap()
+    }
+
+    pub fn as_os_str(&self) -> &OsStr {
+        &self.0
+    }
+
+    pub fn to_str(&self) -> Option<&str> {
+        self.0.to_str()
+    }
+
+    /// First variable like `$VAR` in the location which is not set and has no default value.
+    pub fn unset_var(&self) -> Option<String> {
+        find_unset_var(self.to_str()?, |var| std::env::var(var).ok())
+    }
+
+    /// Location with `~`, `$VAR` and `${VAR:-default}` expanded.
+    /// A location which is not valid utf 8 is returned unchanged.
+    pub fn expanded(&self) -> Cow<'_, OsStr> {
+        match self.to_str() {
+            Some(text) => match expand_location_from_env(text) {
+                Cow::Borrowed(unchanged) => Cow::Borrowed(OsStr::new(unchanged)),
+                Cow::Owned(expanded) => Cow::Owned(OsString::from(expanded)),
+            },
+            None => Cow::Borrowed(self.as_os_str()),
+        }
+    }
+}
+
+impl From<FavoriteLocation> for OsString {
+    fn from(value: FavoriteLocation) -> Self {
+        value.0
+    }
+}
+
+/// Shows the location as text without losing information.
+/// Bytes which are not valid utf 8 are shown escaped like in the JSON file.
+impl Display for FavoriteLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.to_str() {
+            Some(text) => write!(f, "{}", text),
+            None => write!(f, "{}", to_escaped(&self.0)),
+        }
+    }
+}
+
+impl Serialize for FavoriteLocation {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serialize_os_str(&self.0, serializer)
+    }
+}
+
+/// Serializes any path in the same way as a [`FavoriteLocation`] is saved.
+/// Can be used via `#[serde(serialize_with = "serialize_os_str")]`.
+pub fn serialize_os_str<T, S>(os_str: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: AsRef<OsStr> + ?Sized,
+    S: serde::Serializer,
+{
+    let os_str = os_str.as_ref();
+    let repr = match os_str.to_str() {
+        Some(text) => LocationRepr::Text(Cow::Borrowed(text)),
+        None => LocationRepr::Escaped {
+            escaped: to_escaped(os_str),
+        },
+    };
+    repr.serialize(serializer)
+}
+
+impl<'de> Deserialize<'de> for FavoriteLocation {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let location = match LocationRepr::deserialize(deserializer)? {
+            LocationRepr::Text(text) => OsString::from(text.into_owned()),
+            LocationRepr::Escaped { escaped } => {
+                from_escaped(&escaped).map_err(serde::de::Error::custom)?
+            }
+        };
+        Self::new(location).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(unix)]
+fn to_escaped(location: &OsStr) -> String {
+    use std::os::unix::ffi::OsStrExt;
+    escape_bytes(location.as_bytes())
+}
+
+#[cfg(not(unix))]
+fn to_escaped(location: &OsStr) -> String {
+    location.to_string_lossy().into_owned()
+}
+
+#[cfg(unix)]
+fn from_escaped(escaped: &str) -> Result<OsString, String> {
+    use std::os::unix::ffi::OsStringExt;
+    unescape_bytes(escaped).map(OsString::from_vec)
+}
+
+#[cfg(not(unix))]
+fn from_escaped(_escaped: &str) -> Result<OsString, String> {
+    Err("Locations which are not valid utf 8 are only supported on Unix".to_string())
+}
+
+#[cfg(unix)]
+fn escape_bytes(bytes: &[u8]) -> String {
+    fn push_valid(escaped: &mut String, valid: &str) {
+        escaped.push_str(&valid.replace('\\', "\\\\"));
+    }
+
+    let mut escaped = String::new();
+    let mut rest = bytes;
+    while !rest.is_empty() {
+        match std::str::from_utf8(rest) {
+            Ok(valid) => {
+                push_valid(&mut escaped, valid);
+                break;
+            }
+            Err(error) => {
+                let (valid, after_valid) = rest.split_at(error.valid_up_to());
+                // Bytes up to `valid_up_to` are valid utf 8 by definition.
+                push_valid(&mut escaped, std::str::from_utf8(valid).unwrap());
+                let invalid_len = error.error_len().unwrap_or(after_valid.len());
+                let (invalid, after_invalid) = after_valid.split_at(invalid_len);
+                for byte in invalid {
+                    escaped.push_str(&format!("\\x{:02x}", byte));
+                }
+                rest = after_invalid;
+            }
+        }
+    }
+    escaped
+}
+
+#[cfg(unix)]
+fn unescape_bytes(escaped: &str) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::with_capacity(escaped.len());
+    let mut chars = escaped.chars();
+    while let Some(next) = chars.next() {
+        if next != '\\' {
+            let mut buffer = [0; 4];
+            bytes.extend_from_slice(next.encode_utf8(&mut buffer).as_bytes());
+            continue;
+        }
+
+        match chars.next() {
+            Some('\\') => bytes.push(b'\\'),
+            Some('x') => {
+                let hex: String = chars.by_ref().take(2).collect();
+                let byte = u8::from_str_radix(&hex, 16)
+                    .map_err(|_| format!("Invalid escaped byte \"\\x{}\" in location", hex))?;
+                bytes.push(byte);
+            }
+            _ => return Err(format!("Invalid escape sequence in location {:?}", escaped)),
+        }
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn trim_location_and_reject_empty() {
+        assert_eq!(
+            FavoriteLocation::unwrap("/home/some_user"),
+            FavoriteLocation::new("  /home/some_user \n").unwrap()
+        );
+        assert!(matches!(
+            FavoriteLocation::new("  "),
+            Err(InvalidFavoriteFields::EmptyPath)
+        ));
+    }
+
+    #[test]
+    fn save_utf8_location_as_plain_text() {
+        let given = FavoriteLocation::unwrap("/home/some_user/Doküments");
+        let actual = serde_json::to_string(&given).unwrap();
+        assert_eq!(r#""/home/some_user/Doküments""#, actual);
+        let parsed: FavoriteLocation = serde_json::from_str(&actual).unwrap();
+        assert_eq!(given, parsed);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn round_trip_non_utf8_location() {
+        use std::os::unix::ffi::OsStringExt;
+
+        let given = FavoriteLocation::unwrap(OsString::from_vec(
+            b"/home/some_user/caf\xe9/back\\slash".to_vec(),
+        ));
+        let actual = serde_json::to_string(&given).unwrap();
+        assert_eq!(
+            r#"{"escaped":"/home/some_user/caf\\xe9/back\\\\slash"}"#,
+            actual
+        );
+        assert_eq!(r"/home/some_user/caf\xe9/back\\slash", given.to_string());
+        let parsed: FavoriteLocation = serde_json::from_str(&actual).unwrap();
+        assert_eq!(given, parsed);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reject_invalid_escape_sequence() {
+        let actual = serde_json::from_str::<FavoriteLocation>(r#"{"escaped":"/home/\\q"}"#);
+        assert!(actual.is_err());
+        let actual = serde_json::from_str::<FavoriteLocation>(r#"{"escaped":"/home/\\xzz"}"#);
+        assert!(actual.is_err());
+    }
+}
@@ -1,50 +1,197 @@
+use std::io::IsTerminal;
+
+use super::table_spec::{Alignment, BorderStyle, Column, ColumnAlignment};
+use super::SPACING;
 use crate::cli_args::GetParams;
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct DrawParam {
-    clipboard: bool,
+    colors: bool,
     ask_number: bool,
+    max_width: Option<usize>,
+    columns: Vec<Column>,
+    header: bool,
+    border: BorderStyle,
+    alignments: Vec<ColumnAlignment>,
+    highlight: Option<String>,
+    spacing: usize,
+    hyperlinks: bool,
 }
 
-impl From<&GetParams> for DrawParam {
-    fn from(value: &GetParams) -> Self {
-        let (clipboard, ask_number) = (value.copy_has_clipboard(), value.copy_ask_number());
-        Self {
-            clipboard,
-            ask_number,
-        }
-    }
-}
-impl From<GetParams> for DrawParam {
-    fn from(value: GetParams) -> Self {
-        let (clipboard, ask_number) = (value.copy_has_clipboard(), value.copy_ask_number());
-        Self {
-            clipboard,
-            ask_number,
-        }
+impl DrawParam {
+    /// `colors` decides if the table contains colors.
+    pub fn from_get_params(value: &GetParams, colors: bool) -> Self {
+        let ask_number = value.copy_ask_number();
+        let max_width = if value.copy_has_clipboard() || value.copy_no_truncate() {
+            None
+        } else {
+            detect_terminal_width()
+        };
+        Self::new(colors, ask_number)
+            .with_max_width(max_width)
+            .with_columns(value.get_columns().map(<[Column]>::to_vec))
+            .with_git_columns(value.copy_git())
+            .with_header(value.copy_header())
+            .with_border(value.copy_border())
+            .with_alignments(value.get_alignments().to_vec())
+            .with_spacing(value.copy_spacing().unwrap_or(SPACING))
+            // Content for the clipboard never contains links.
+            .with_hyperlinks(
+                !value.copy_has_clipboard()
+                    && value
+                        .copy_hyperlinks()
+                        .should_link(std::io::stdout().is_terminal()),
+            )
+            .with_highlight(
+                value
+                    .get_name()
+                    .filter(|_| value.copy_fuzzy())
+                    .map(str::to_string),
+            )
     }
-}
 
-impl DrawParam {
-    pub fn new(clipboard: bool, ask_number: bool) -> Self {
+    pub fn new(colors: bool, ask_number: bool) -> Self {
         Self {
-            clipboard,
+            colors,
             ask_number,
+            max_width: None,
+            columns: default_columns(ask_number),
+            header: false,
+            border: BorderStyle::default(),
+            alignments: Vec::new(),
+            highlight: None,
+            spacing: SPACING,
+            hyperlinks: false,
         }
     }
 
+    /// Table without colors.
     pub fn new_for_clipboard(ask_number: bool) -> Self {
-        Self {
-            clipboard: true,
-            ask_number,
+        Self::new(false, ask_number)
+    }
+
+    /// Paths are shortened in the middle so a line takes up at most `max_width` columns.
+    /// No path is shortened if `None` is given.
+    pub fn with_max_width(self, max_width: Option<usize>) -> Self {
+        Self { max_width, ..self }
+    }
+
+    /// Shows the given columns in the given order.
+    /// If `None` is given then name and path are shown.
+    /// The index column is put in front if numbers are asked for and the index is not shown.
+    pub fn with_columns(self, columns: Option<Vec<Column>>) -> Self {
+        let columns = match columns {
+            Some(mut columns) => {
+                if self.ask_number && !columns.contains(&Column::Index) {
+                    columns.insert(0, Column::Index);
+                }
+                columns
+            }
+            None => default_columns(self.ask_number),
+        };
+        Self { columns, ..self }
+    }
+
+    pub fn with_header(self, header: bool) -> Self {
+        Self { header, ..self }
+    }
+
+    pub fn with_border(self, border: BorderStyle) -> Self {
+        Self { border, ..self }
+    }
+
+    /// Appends the columns branch and dirty if not already shown.
+    pub fn with_git_columns(mut self, git: bool) -> Self {
+        if git {
+            for column in [Column::Branch, Column::Dirty] {
+                if !self.columns.contains(&column) {
+                    self.columns.push(column);
+                }
+            }
         }
+        self
+    }
+
+    pub fn with_alignments(self, alignments: Vec<ColumnAlignment>) -> Self {
+        Self { alignments, ..self }
+    }
+
+    /// Number of spaces between columns without borders.
+    pub fn with_spacing(self, spacing: usize) -> Self {
+        Self { spacing, ..self }
+    }
+
+    /// Paths are clickable links to the expanded location.
+    pub fn with_hyperlinks(self, hyperlinks: bool) -> Self {
+        Self { hyperlinks, ..self }
+    }
+
+    /// Parts of names matching the given text are highlighted if colors are used.
+    pub fn with_highlight(self, highlight: Option<String>) -> Self {
+        Self { highlight, ..self }
+    }
+
+    pub fn colors(&self) -> bool {
+        self.colors
     }
 
-    pub fn clipboard(&self) -> bool {
-        self.clipboard
+    pub fn spacing(&self) -> usize {
+        self.spacing
+    }
+
+    pub fn hyperlinks(&self) -> bool {
+        self.hyperlinks
+    }
+
+    pub fn highlight(&self) -> Option<&str> {
+        self.highlight.as_deref()
     }
 
     pub fn ask_number(&self) -> bool {
         self.ask_number
     }
+
+    pub fn max_width(&self) -> Option<usize> {
+        self.max_width
+    }
+
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    /// A markdown table always has a header row.
+    pub fn header(&self) -> bool {
+        self.header || self.border == BorderStyle::Markdown
+    }
+
+    pub fn border(&self) -> BorderStyle {
+        self.border
+    }
+
+    /// Alignment of a column. The last alignment given for a column wins.
+    /// Columns are aligned to the left by default.
+    pub fn alignment(&self, column: Column) -> Alignment {
+        self.alignments
+            .iter()
+            .rev()
+            .find(|given| given.column == column)
+            .map(|given| given.alignment)
+            .unwrap_or_default()
+    }
+}
+
+fn default_columns(ask_number: bool) -> Vec<Column> {
+    if ask_number {
+        vec![Column::Index, Column::Name, Column::Path]
+    } else {
+        vec![Column::Name, Column::Path]
+    }
+}
+
+/// Width of the terminal in columns if stdout is a terminal.
+fn detect_terminal_width() -> Option<usize> {
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+    terminal_size::terminal_size().map(|(terminal_size::Width(width), _)| usize::from(width))
 }
@@ -0,0 +1,102 @@
+use std::path::Path;
+
+use clap::ValueEnum;
+use serde::Deserialize;
+
+/// Decides if paths in the table are clickable links via the escape sequence OSC 8.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HyperlinkMode {
+    /// Links only if stdout is a terminal which is not "dumb".
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl HyperlinkMode {
+    pub fn should_link(self, is_terminal: bool) -> bool {
+        match self {
+            HyperlinkMode::Always => true,
+            HyperlinkMode::Never => false,
+            HyperlinkMode::Auto => {
+                is_terminal && std::env::var_os("TERM").is_some_and(|term| term != "dumb")
+            }
+        }
+    }
+}
+
+/// Surrounds the text with the escape sequences of a link to the url.
+/// The text keeps its width on the terminal.
+pub fn wrap_in_hyperlink(text: &str, url: &str) -> String {
+    format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
+}
+
+/// Url like "file://host/home/some_user" with percent-encoded path.
+/// Returns none for a relative path.
+pub fn file_url(path: &Path, host: &str) -> Option<String> {
+    if !path.is_absolute() {
+        return None;
+    }
+    Some(format!(
+        "file://{}{}",
+        host,
+        percent_encode(&path_bytes(path))
+    ))
+}
+
+/// Name of this computer. Links without host are created if the name is not valid utf 8.
+pub fn local_host() -> String {
+    gethostname::gethostname().into_string().unwrap_or_default()
+}
+
+#[cfg(unix)]
+fn path_bytes(path: &Path) -> Vec<u8> {
+    use std::os::unix::ffi::OsStrExt;
+    path.as_os_str().as_bytes().to_vec()
+}
+
+/// Backslashes are replaced and a drive letter gets a leading slash like "/C:/Users".
+#[cfg(not(unix))]
+fn path_bytes(path: &Path) -> Vec<u8> {
+    let path = path.to_string_lossy().replace('\\', "/");
+    if path.starts_with('/') {
+        path.into_bytes()
+    } else {
+        format!("/{}", path).into_bytes()
+    }
+}
+
+/// Keeps unreserved characters and separators of an url path. Every other byte is encoded.
+fn percent_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len());
+    for &byte in bytes {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                encoded.push(char::from(byte))
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[cfg(all(test, unix))]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn create_percent_encoded_file_urls() {
+        fn assert_case(given: &str, expected: Option<&str>) {
+            let actual = file_url(Path::new(given), "laptop");
+            assert_eq!(expected.map(str::to_string), actual, "Given: {}", given);
+        }
+
+        assert_case("/home/some_user", Some("file://laptop/home/some_user"));
+        assert_case(
+            "/home/some user/Doküments#1",
+            Some("file://laptop/home/some%20user/Dok%C3%BCments%231"),
+        );
+        assert_case("relative/path", None);
+    }
+}
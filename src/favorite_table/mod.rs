@@ -1,193 +1,477 @@
 pub use draw_params::DrawParam;
+pub use hyperlink::HyperlinkMode;
+pub use table_spec::{Alignment, BorderStyle, Column, ColumnAlignment};
+pub use text_width::{display_width, pad_from_right_until};
 mod draw_params;
+mod hyperlink;
+mod table_spec;
+mod text_width;
 
 use crate::app::term_colors;
 
 use crate::favorite_folder_record::FavoriteFolderPath;
-use shellexpand;
-use std::borrow::Cow;
-use std::path::Path;
+use crate::git_info::GitInfo;
+use crate::location_kind::LocationKind;
+use hyperlink::{file_url, local_host, wrap_in_hyperlink};
+use text_width::{ellipsize_middle, pad_from_left_until};
 
+/// Cells of one favorite in the order of the shown columns.
 #[derive(Debug)]
-struct LineNameAndPath {
-    name: String,
-    path: String,
+struct Row {
+    cells: Vec<String>,
+    kind: Option<LocationKind>,
+    /// Url for the path cell if paths are shown as links.
+    link: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RulePosition {
+    Top,
+    BelowHeader,
+    Bottom,
 }
 
 pub fn draw_favorite_table(all_locations: &[FavoriteFolderPath], params: DrawParam) -> String {
-    let ask_numbers = params.ask_number();
-    if params.clipboard() {
-        draw_without_colors(all_locations, ask_numbers)
+    if params.colors() {
+        draw_with_colors(all_locations, &params)
     } else {
-        draw_with_colors(all_locations, ask_numbers)
+        draw_without_colors(all_locations, &params)
     }
 }
 
-fn draw_without_colors(all_locations: &[FavoriteFolderPath], ask_numbers: bool) -> String {
-    prepare_drawing(all_locations, ask_numbers)
-        .into_iter()
-        .map(|line| format!("{}{}", line.name, line.path))
-        .collect::<Vec<String>>()
-        .join("\n")
+fn draw_without_colors(all_locations: &[FavoriteFolderPath], params: &DrawParam) -> String {
+    draw(all_locations, params, false)
 }
 
-fn draw_with_colors(all_locations: &[FavoriteFolderPath], ask_numbers: bool) -> String {
-    prepare_drawing(all_locations, ask_numbers)
-        .into_iter()
-        .map(|line| {
-            let colored_path = get_colored_path_if_no_clipboard(&line.path);
-            format!("{}{}", line.name, colored_path)
-        })
-        .collect::<Vec<String>>()
-        .join("\n")
+fn draw_with_colors(all_locations: &[FavoriteFolderPath], params: &DrawParam) -> String {
+    draw(all_locations, params, true)
+}
+
+fn draw(all_locations: &[FavoriteFolderPath], params: &DrawParam, with_colors: bool) -> String {
+    let columns = params.columns();
+    let header: Option<Vec<String>> = params.header().then(|| {
+        columns
+            .iter()
+            .map(|column| column.header().to_string())
+            .collect()
+    });
+    let mut rows = prepare_drawing(all_locations, params, with_colors);
+    if let Some(max_width) = params.max_width() {
+        truncate_paths(&mut rows, header.as_deref(), params, max_width);
+    }
+    if params.border() == BorderStyle::Markdown {
+        // Escaped after truncation so an escape is never cut in half.
+        for cell in rows.iter_mut().flat_map(|row| row.cells.iter_mut()) {
+            *cell = cell.replace('|', "\\|");
+        }
+    }
+    let widths = calc_column_widths(&rows, header.as_deref(), params);
+
+    let mut lines = Vec::new();
+    lines.extend(draw_rule(&widths, params, RulePosition::Top));
+    if let Some(header) = header {
+        lines.push(draw_line(&header, &widths, params, |_, cell| {
+            if with_colors {
+                paint_trimmed(cell, term_colors::color_header_msg)
+            } else {
+                cell
+            }
+        }));
+        lines.extend(draw_rule(&widths, params, RulePosition::BelowHeader));
+    }
+    for row in rows {
+        // Escape sequences are only added after the widths of the columns are known.
+        let colorize = |column: Column, cell: String| {
+            paint_trimmed(cell, |text| {
+                let text = match (column, &row.link) {
+                    (Column::Path, Some(url)) => wrap_in_hyperlink(text, url),
+                    _ => text.to_string(),
+                };
+                match (column, row.kind) {
+                    _ if !with_colors => text,
+                    (Column::Path | Column::Exists | Column::Kind, Some(kind)) => {
+                        color_by_kind(kind, &text)
+                    }
+                    (Column::Name, _) => term_colors::color_name_msg(&text, params.highlight()),
+                    (Column::Index, _) => term_colors::color_index_msg(&text),
+                    _ => text,
+                }
+            })
+        };
+        lines.push(draw_line(&row.cells, &widths, params, colorize));
+    }
+    lines.extend(draw_rule(&widths, params, RulePosition::Bottom));
+
+    lines.join("\n")
 }
 
 fn prepare_drawing(
     all_locations: &[FavoriteFolderPath],
-    ask_numbers: bool,
-) -> Vec<LineNameAndPath> {
-    let spacing_padding = get_spacing_padding();
-
-    let records_without_line_numbers =
-        construct_recores_without_numbers(all_locations, &spacing_padding);
-    let lines = if ask_numbers {
-        handle_line_number_if_needed(
-            all_locations,
-            records_without_line_numbers,
-            &spacing_padding,
-        )
+    params: &DrawParam,
+    with_colors: bool,
+) -> Vec<Row> {
+    let columns = params.columns();
+    let needs_kind = with_colors
+        || columns
+            .iter()
+            .any(|column| matches!(column, Column::Exists | Column::Kind | Column::Icon));
+    let host = params.hyperlinks().then(local_host);
+    let needs_git = columns
+        .iter()
+        .any(|column| matches!(column, Column::Branch | Column::Dirty));
+    let git_infos = if needs_git {
+        let paths: Vec<_> = all_locations
+            .iter()
+            .map(|location| location.path())
+            .collect();
+        GitInfo::detect_all(&paths)
     } else {
-        records_without_line_numbers.collect()
+        Vec::new()
     };
 
-    return lines;
-
-    fn calc_max_width(all_locations: &[FavoriteFolderPath]) -> usize {
-        all_locations
-            .iter()
-            .fold(0, |akk, next| akk.max(next.get_name().len()))
-    }
-
-    fn construct_recores_without_numbers<'a>(
-        all_locations: &'a [FavoriteFolderPath],
-        spacing_padding: &'a str,
-    ) -> impl Iterator<Item = LineNameAndPath> + 'a {
-        let max_width = calc_max_width(all_locations);
-        all_locations.iter().map(move |next_record| {
-            let name = next_record.get_name();
-            assert!(
-                name.len() <= max_width,
-                "Could not find max width for all labels"
-            );
-
-            let padded_name = pad_from_right_until(name, max_width);
-            let raw_path = next_record.path_str();
-
-            let padded_name = format!("{padded_name}{spacing_padding}");
-            LineNameAndPath {
-                name: padded_name,
-                path: raw_path.to_string(),
-            }
+    all_locations
+        .iter()
+        .enumerate()
+        .map(|(index, location)| {
+            let kind = needs_kind.then(|| location.kind());
+            let git = git_infos.get(index).and_then(Option::as_ref);
+            let cells = columns
+                .iter()
+                .map(|column| match (column, kind) {
+                    (Column::Index, _) => (index + 1).to_string(),
+                    (Column::Name, _) => location.get_name().to_string(),
+                    (Column::Path, _) => location.location().to_string(),
+                    (Column::Exists, Some(kind)) => {
+                        if kind.exists() { "yes" } else { "no" }.to_string()
+                    }
+                    (Column::Kind, Some(kind)) => kind.label().to_string(),
+                    (Column::Icon, Some(kind)) => kind.icon().to_string(),
+                    (Column::Exists | Column::Kind | Column::Icon, None) => {
+                        unreachable!("Kind of location is always looked at for these columns")
+                    }
+                    (Column::Tags, _) => location
+                        .tags()
+                        .iter()
+                        .map(|tag| tag.as_str())
+                        .collect::<Vec<_>>()
+                        .join(","),
+                    (Column::Branch, _) => git.map(|git| git.head.to_string()).unwrap_or_default(),
+                    // Left empty if the command git could not tell.
+                    (Column::Dirty, _) => match git.and_then(|git| git.dirty) {
+                        Some(true) => "yes".to_string(),
+                        Some(false) => "no".to_string(),
+                        None => String::new(),
+                    },
+                })
+                .collect();
+            let link = host
+                .as_deref()
+                .and_then(|host| file_url(&location.path(), host));
+            Row { cells, kind, link }
         })
-    }
-
-    fn handle_line_number_if_needed(
-        all_locations: &[FavoriteFolderPath],
-        records_without_line_numbers: impl Iterator<Item = LineNameAndPath>,
-        spacing_padding: &str,
-    ) -> Vec<LineNameAndPath> {
-        let max_number_width = all_locations.len().to_string().chars().count();
+        .collect()
+}
 
-        records_without_line_numbers
-            .enumerate()
-            .map(move |(index, line)| {
-                let number = index + 1;
-                let to_pad = number.to_string();
-                let padded_number = format!(
-                    "{}{}{}",
-                    pad_from_right_until(&to_pad, max_number_width),
-                    spacing_padding,
-                    line.name
-                );
-
-                LineNameAndPath {
-                    name: padded_number,
-                    path: line.path,
-                }
-            })
-            .collect()
+/// Number of columns taken up by borders and spaces between the cells of one line.
+fn calc_border_width(params: &DrawParam) -> usize {
+    let number_of_columns = params.columns().len();
+    match params.border() {
+        BorderStyle::None => params.spacing() * number_of_columns.saturating_sub(1),
+        BorderStyle::Ascii | BorderStyle::Unicode | BorderStyle::Markdown => {
+            3 * number_of_columns + 1
+        }
     }
 }
 
-fn get_colored_path_if_no_clipboard(raw_path: &str) -> String {
-    let expanded = shellexpand::tilde(raw_path);
-    let expanded_path = Path::new(expanded.as_ref());
-    check_if_exits(expanded_path, raw_path)
+fn calc_column_widths(rows: &[Row], header: Option<&[String]>, params: &DrawParam) -> Vec<usize> {
+    // A markdown separator needs at least 3 dashes.
+    let min_width = match params.border() {
+        BorderStyle::Markdown => 3,
+        _ => 0,
+    };
+    let mut widths = vec![min_width; params.columns().len()];
+    for cells in header
+        .into_iter()
+        .chain(rows.iter().map(|row| row.cells.as_slice()))
+    {
+        for (width, cell) in widths.iter_mut().zip(cells) {
+            *width = (*width).max(display_width(cell));
+        }
+    }
+    widths
 }
 
-fn check_if_exits(path: &Path, to_check_color: &str) -> String {
-    if path.exists() {
-        term_colors::color_exists_msg(to_check_color)
-    } else {
-        term_colors::color_not_found(to_check_color)
+/// Shortens paths in the middle so every line takes up at most `max_width` columns.
+fn truncate_paths(
+    rows: &mut [Row],
+    header: Option<&[String]>,
+    params: &DrawParam,
+    max_width: usize,
+) {
+    let Some(path_index) = params
+        .columns()
+        .iter()
+        .position(|column| *column == Column::Path)
+    else {
+        return;
+    };
+
+    let widths = calc_column_widths(rows, header, params);
+    let other_width: usize = widths
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| *index != path_index)
+        .map(|(_, width)| width)
+        .sum();
+    let left_width = max_width.saturating_sub(other_width + calc_border_width(params));
+
+    for row in rows {
+        let path = &mut row.cells[path_index];
+        *path = ellipsize_middle(path, left_width).into_owned();
     }
 }
 
-/// Size of space between columns in a output.
-/// Example: `Spacing` is 2 then between name and path is 2 whitespaces in the output for the
-/// user.
-const SPACING: usize = 2;
+fn draw_line(
+    cells: &[String],
+    widths: &[usize],
+    params: &DrawParam,
+    colorize: impl Fn(Column, String) -> String,
+) -> String {
+    let border = params.border();
+    let last_index = cells.len().saturating_sub(1);
+    let aligned: Vec<String> = params
+        .columns()
+        .iter()
+        .zip(cells.iter().zip(widths))
+        .enumerate()
+        .map(|(index, (column, (cell, width)))| {
+            let aligned = match params.alignment(*column) {
+                // Avoids trailing spaces at the end of a line without borders.
+                Alignment::Left if border == BorderStyle::None && index == last_index => {
+                    cell.to_string()
+                }
+                Alignment::Left => pad_from_right_until(cell, *width).into_owned(),
+                Alignment::Right => pad_from_left_until(cell, *width).into_owned(),
+            };
+            colorize(*column, aligned)
+        })
+        .collect();
 
-fn get_spacing_padding() -> String {
-    " ".repeat(SPACING)
+    match border {
+        BorderStyle::None => aligned.join(&" ".repeat(params.spacing())),
+        BorderStyle::Ascii | BorderStyle::Markdown => format!("| {} |", aligned.join(" | ")),
+        BorderStyle::Unicode => format!("│ {} │", aligned.join(" │ ")),
+    }
 }
 
-/// # Summary
-///
-/// Makes sure the return value is at least as long as `max_with` chars.
-///
-/// # Returns
-///
-/// Either returns the `to_pad` unchanged as ref or as new owned value with spaces appended from right
-/// so the string is exactly `max_with` chars long.
-fn pad_from_right_until(to_pad: &str, max_width: usize) -> Cow<'_, str> {
-    let actual_len = to_pad.len();
-
-    if actual_len >= max_width {
-        return to_pad.into();
+fn draw_rule(widths: &[usize], params: &DrawParam, position: RulePosition) -> Option<String> {
+    let dashes = |horizontal: &str| -> Vec<String> {
+        widths
+            .iter()
+            .map(|width| horizontal.repeat(width + 2))
+            .collect()
+    };
+    match (params.border(), position) {
+        (BorderStyle::None, _) => None,
+        (BorderStyle::Ascii, _) => Some(format!("+{}+", dashes("-").join("+"))),
+        (BorderStyle::Unicode, RulePosition::Top) => Some(format!("┌{}┐", dashes("─").join("┬"))),
+        (BorderStyle::Unicode, RulePosition::BelowHeader) => {
+            Some(format!("├{}┤", dashes("─").join("┼")))
+        }
+        (BorderStyle::Unicode, RulePosition::Bottom) => {
+            Some(format!("└{}┘", dashes("─").join("┴")))
+        }
+        (BorderStyle::Markdown, RulePosition::BelowHeader) => {
+            let separators: Vec<String> = params
+                .columns()
+                .iter()
+                .zip(widths)
+                .map(|(column, width)| match params.alignment(*column) {
+                    Alignment::Left => "-".repeat(*width),
+                    Alignment::Right => format!("{}:", "-".repeat(width - 1)),
+                })
+                .collect();
+            Some(format!("| {} |", separators.join(" | ")))
+        }
+        (BorderStyle::Markdown, _) => None,
     }
+}
 
-    let diff = max_width - actual_len;
-    let padding = " ".repeat(diff);
-    let mut padded = String::from(to_pad);
-    padded.push_str(&padding);
+fn color_by_kind(kind: LocationKind, to_color: &str) -> String {
+    match kind {
+        LocationKind::Dir => term_colors::color_exists_msg(to_color),
+        LocationKind::File => term_colors::color_file_msg(to_color),
+        LocationKind::SymlinkToDir | LocationKind::SymlinkToFile => {
+            term_colors::color_symlink_msg(to_color)
+        }
+        LocationKind::BrokenSymlink => term_colors::color_broken_symlink_msg(to_color),
+        LocationKind::Special => term_colors::color_special_msg(to_color),
+        LocationKind::Missing => term_colors::color_not_found(to_color),
+        LocationKind::PermissionDenied => term_colors::color_permission_denied_msg(to_color),
+        LocationKind::IoError => term_colors::color_io_error_msg(to_color),
+    }
+}
 
-    padded.into()
+/// Paints a cell without the spaces used for alignment.
+fn paint_trimmed(cell: String, paint: impl Fn(&str) -> String) -> String {
+    let trimmed = cell.trim();
+    if trimmed.is_empty() {
+        return cell;
+    }
+    let start = cell.len() - cell.trim_start().len();
+    let end = start + trimmed.len();
+    format!("{}{}{}", &cell[..start], paint(trimmed), &cell[end..])
 }
 
+/// Default size of space between columns in a output.
+/// Example: `Spacing` is 2 then between name and path is 2 whitespaces in the output for the
+/// user.
+const SPACING: usize = 2;
+
 #[cfg(test)]
 mod testing {
+    use crate::favorite_location::FavoriteLocation;
+    use crate::trimmed_not_empty_text::NonEmptyText;
     use crate::AllFavorites;
 
     use super::*;
     const INPUT: &str = include_str!("longer_test_input.json");
+    const INPUT_UNICODE: &str = include_str!("unicode_test_input.json");
 
     fn given_initial() -> AllFavorites {
         let parsed: Vec<FavoriteFolderPath> = serde_json::from_str(INPUT).unwrap();
         AllFavorites::new(parsed)
     }
 
+    fn given_unicode_initial() -> AllFavorites {
+        let parsed: Vec<FavoriteFolderPath> = serde_json::from_str(INPUT_UNICODE).unwrap();
+        AllFavorites::new(parsed)
+    }
+
     #[test]
     fn list_all_every_favorite() {
         let given = given_initial();
-        let actual = draw_without_colors(given.as_slice(), false);
+        let actual = draw_without_colors(given.as_slice(), &DrawParam::new_for_clipboard(false));
         insta::assert_snapshot!(actual);
     }
 
     #[test]
     fn list_all_with_prompt_every_favorite() {
         let given = given_initial();
-        let actual = draw_without_colors(given.as_slice(), true);
+        let actual = draw_without_colors(given.as_slice(), &DrawParam::new_for_clipboard(true));
+        insta::assert_snapshot!(actual);
+    }
+
+    #[test]
+    fn align_names_by_display_width() {
+        let given = given_unicode_initial();
+        let actual = draw_without_colors(given.as_slice(), &DrawParam::new_for_clipboard(true));
         insta::assert_snapshot!(actual);
     }
+
+    #[test]
+    fn truncate_paths_to_max_width() {
+        let given = given_unicode_initial();
+        let params = DrawParam::new_for_clipboard(false).with_max_width(Some(35));
+        let actual = draw_without_colors(given.as_slice(), &params);
+        insta::assert_snapshot!(actual);
+    }
+
+    #[test]
+    fn draw_ascii_borders_with_header() {
+        let given = given_initial();
+        let params = DrawParam::new_for_clipboard(true)
+            .with_header(true)
+            .with_border(BorderStyle::Ascii);
+        let actual = draw_without_colors(given.as_slice(), &params);
+        insta::assert_snapshot!(actual);
+    }
+
+    #[test]
+    fn draw_unicode_borders_with_selected_columns_and_alignment() {
+        let given = given_unicode_initial();
+        let params = DrawParam::new_for_clipboard(false)
+            .with_columns(Some(vec![Column::Path, Column::Index, Column::Name]))
+            .with_border(BorderStyle::Unicode)
+            .with_alignments(vec![ColumnAlignment {
+                column: Column::Index,
+                alignment: Alignment::Right,
+            }]);
+        let actual = draw_without_colors(given.as_slice(), &params);
+        insta::assert_snapshot!(actual);
+    }
+
+    #[test]
+    fn draw_markdown_table() {
+        let given = given_initial();
+        let params = DrawParam::new_for_clipboard(false)
+            .with_columns(Some(vec![Column::Index, Column::Name, Column::Exists]))
+            .with_border(BorderStyle::Markdown)
+            .with_alignments(vec![ColumnAlignment {
+                column: Column::Index,
+                alignment: Alignment::Right,
+            }]);
+        let actual = draw_without_colors(given.as_slice(), &params);
+        insta::assert_snapshot!(actual);
+    }
+
+    #[test]
+    fn escape_pipes_in_markdown_table() {
+        let given = AllFavorites::from_iter([
+            FavoriteFolderPath::new(
+                NonEmptyText::unwrap("a|b"),
+                FavoriteLocation::unwrap("/home/some_user/x|y"),
+            ),
+            FavoriteFolderPath::new(
+                NonEmptyText::unwrap("plain"),
+                FavoriteLocation::unwrap("/home/some_user"),
+            ),
+        ]);
+        let params = DrawParam::new_for_clipboard(false)
+            .with_columns(Some(vec![Column::Name, Column::Path]))
+            .with_border(BorderStyle::Markdown);
+        let actual = draw_without_colors(given.as_slice(), &params);
+        insta::assert_snapshot!(actual);
+    }
+
+    #[test]
+    fn draw_kind_and_icon_of_locations() {
+        let given = given_initial();
+        let params = DrawParam::new_for_clipboard(false)
+            .with_columns(Some(vec![Column::Icon, Column::Name, Column::Kind]))
+            .with_header(true);
+        let actual = draw_without_colors(given.as_slice(), &params);
+        insta::assert_snapshot!(actual);
+    }
+
+    #[test]
+    fn prepend_index_if_asked_for_number() {
+        let params =
+            DrawParam::new_for_clipboard(true).with_columns(Some(vec![Column::Name, Column::Path]));
+        assert_eq!(
+            &[Column::Index, Column::Name, Column::Path],
+            params.columns()
+        );
+    }
+
+    #[test]
+    fn keep_alignment_with_hyperlinks() {
+        let given = given_unicode_initial();
+        let params = DrawParam::new_for_clipboard(false)
+            .with_border(BorderStyle::Ascii)
+            .with_columns(Some(vec![Column::Path, Column::Name]));
+        let expected = draw_without_colors(given.as_slice(), &params);
+
+        let actual = draw_without_colors(given.as_slice(), &params.with_hyperlinks(true));
+        assert!(actual.contains("\x1b]8;;file://"), "Actual: {}", actual);
+        let without_links: String = actual
+            .split("\x1b]8;;")
+            .enumerate()
+            .map(|(index, part)| match index {
+                0 => part,
+                _ => part.split_once("\x1b\\").map_or(part, |(_, after)| after),
+            })
+            .collect();
+        assert_eq!(expected, without_links);
+    }
 }
@@ -0,0 +1,109 @@
+use std::str::FromStr;
+
+use clap::ValueEnum;
+
+/// Column which can be shown in the table of favorites.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Column {
+    /// Line number starting from 1.
+    Index,
+    /// Name of a favorite.
+    Name,
+    /// Location of a favorite as saved.
+    Path,
+    /// "yes" if the location exists otherwise "no".
+    Exists,
+    /// Kind of the location like "dir", "file", "link→dir" or "broken link".
+    Kind,
+    /// Symbol for the kind of the location.
+    Icon,
+    /// Checked out branch like "main" or "detached at 3f78685" if the location is within a git work tree.
+    Branch,
+    /// "yes" if the git work tree of the location has uncommitted changes otherwise "no".
+    Dirty,
+    /// Tags of a favorite separated by commas.
+    Tags,
+}
+
+impl Column {
+    pub fn header(&self) -> &'static str {
+        match self {
+            Column::Index => "#",
+            Column::Name => "Name",
+            Column::Path => "Path",
+            Column::Exists => "Exists",
+            Column::Kind => "Kind",
+            Column::Icon => "",
+            Column::Branch => "Branch",
+            Column::Dirty => "Dirty",
+            Column::Tags => "Tags",
+        }
+    }
+}
+
+/// Style of the lines drawn around and between the cells of the table.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BorderStyle {
+    /// Columns are only separated by spaces.
+    #[default]
+    None,
+    /// Lines are drawn with "+", "-" and "|".
+    Ascii,
+    /// Lines are drawn with box drawing characters.
+    Unicode,
+    /// Table in the format of markdown. Always shows a header row.
+    Markdown,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Alignment {
+    #[default]
+    Left,
+    Right,
+}
+
+/// Alignment for one column given like "<column>=<left|right>".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColumnAlignment {
+    pub column: Column,
+    pub alignment: Alignment,
+}
+
+impl FromStr for ColumnAlignment {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (column, alignment) = s
+            .split_once('=')
+            .ok_or_else(|| format!("Expected <column>=<left|right> but got \"{}\"", s))?;
+        let column = <Column as ValueEnum>::from_str(column.trim(), true)?;
+        let alignment = <Alignment as ValueEnum>::from_str(alignment.trim(), true)?;
+        Ok(Self { column, alignment })
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn parse_column_alignment() {
+        assert_eq!(
+            Ok(ColumnAlignment {
+                column: Column::Index,
+                alignment: Alignment::Right
+            }),
+            "index=right".parse()
+        );
+        assert_eq!(
+            Ok(ColumnAlignment {
+                column: Column::Path,
+                alignment: Alignment::Left
+            }),
+            " Path = LEFT ".parse()
+        );
+        assert!("index".parse::<ColumnAlignment>().is_err());
+        assert!("index=center".parse::<ColumnAlignment>().is_err());
+        assert!("size=left".parse::<ColumnAlignment>().is_err());
+    }
+}
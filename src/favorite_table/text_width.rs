@@ -0,0 +1,173 @@
+use std::borrow::Cow;
+use std::path::{MAIN_SEPARATOR, MAIN_SEPARATOR_STR};
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+const ELLIPSIS: &str = "…";
+
+/// Number of columns a text takes up on a terminal.
+/// Every grapheme like an emoji made up of several code points takes up at most 2 columns.
+pub fn display_width(text: &str) -> usize {
+    text.graphemes(true).map(grapheme_width).sum()
+}
+
+fn grapheme_width(grapheme: &str) -> usize {
+    grapheme.width().min(2)
+}
+
+/// # Summary
+///
+/// Makes sure the return value takes up at least `max_width` columns on a terminal.
+///
+/// # Returns
+///
+/// Either returns the `to_pad` unchanged as ref or as new owned value with spaces appended from right
+/// so the string takes up exactly `max_width` columns.
+pub fn pad_from_right_until(to_pad: &str, max_width: usize) -> Cow<'_, str> {
+    let actual_width = display_width(to_pad);
+
+    if actual_width >= max_width {
+        return to_pad.into();
+    }
+
+    let diff = max_width - actual_width;
+    let padding = " ".repeat(diff);
+    let mut padded = String::from(to_pad);
+    padded.push_str(&padding);
+
+    padded.into()
+}
+
+/// Same as [`pad_from_right_until`] but the spaces are prepended from left.
+pub fn pad_from_left_until(to_pad: &str, max_width: usize) -> Cow<'_, str> {
+    let actual_width = display_width(to_pad);
+
+    if actual_width >= max_width {
+        return to_pad.into();
+    }
+
+    let mut padded = " ".repeat(max_width - actual_width);
+    padded.push_str(to_pad);
+
+    padded.into()
+}
+
+/// # Summary
+///
+/// Shortens a path to take up at most `max_width` columns by replacing folders in the middle
+/// with an ellipsis like "/home/u/…/deep/dir".
+///
+/// # Returns
+///
+/// Returns the path unchanged if it fits.
+/// If not even the last folder fits after an ellipsis, then only the end of the last folder is
+/// kept after an ellipsis.
+pub fn ellipsize_middle(path: &str, max_width: usize) -> Cow<'_, str> {
+    if display_width(path) <= max_width {
+        return path.into();
+    }
+
+    let segments: Vec<&str> = path.split(MAIN_SEPARATOR).collect();
+    let join = |head: usize, tail: usize| {
+        format!(
+            "{}{sep}{ELLIPSIS}{sep}{}",
+            segments[..head].join(MAIN_SEPARATOR_STR),
+            segments[segments.len() - tail..].join(MAIN_SEPARATOR_STR),
+            sep = MAIN_SEPARATOR
+        )
+    };
+
+    let (mut head, mut tail) = (1, 1);
+    if segments.len() < 3 || display_width(&join(head, tail)) > max_width {
+        return keep_end(path, max_width).into();
+    }
+
+    // Grows the kept folders from both ends in turns so start and end stay balanced.
+    while head + tail < segments.len() - 1 {
+        let (more_head, more_tail) = if tail <= head {
+            ((head, tail + 1), (head + 1, tail))
+        } else {
+            ((head + 1, tail), (head, tail + 1))
+        };
+        match [more_head, more_tail]
+            .into_iter()
+            .find(|&(head, tail)| display_width(&join(head, tail)) <= max_width)
+        {
+            Some((next_head, next_tail)) => (head, tail) = (next_head, next_tail),
+            None => break,
+        }
+    }
+
+    join(head, tail).into()
+}
+
+fn keep_end(text: &str, max_width: usize) -> String {
+    if max_width == 0 {
+        return String::new();
+    }
+
+    let mut width = display_width(ELLIPSIS);
+    let mut kept: Vec<&str> = Vec::new();
+    for grapheme in text.graphemes(true).rev() {
+        width += grapheme_width(grapheme);
+        if width > max_width {
+            break;
+        }
+        kept.push(grapheme);
+    }
+
+    let mut shortened = String::from(ELLIPSIS);
+    shortened.extend(kept.into_iter().rev());
+    shortened
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn width_of_wide_and_combined_characters() {
+        fn assert_case(given: &str, expected: usize) {
+            let actual = display_width(given);
+            assert_eq!(expected, actual, "Given: {}", given);
+        }
+
+        assert_case("dev_rust", 8);
+        assert_case("Übung", 5);
+        assert_case("U\u{0308}bung", 5);
+        assert_case("日本語", 6);
+        assert_case("🦀rust", 6);
+        assert_case("👨‍👩‍👧", 2);
+    }
+
+    #[test]
+    fn pad_by_display_width() {
+        assert_eq!("日本  ", pad_from_right_until("日本", 6));
+        assert_eq!("Übung ", pad_from_right_until("Übung", 6));
+        assert_eq!("dev_rust", pad_from_right_until("dev_rust", 4));
+        assert_eq!("  日本", pad_from_left_until("日本", 6));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn shorten_path_in_the_middle() {
+        fn assert_case(given: &str, max_width: usize, expected: &str) {
+            let actual = ellipsize_middle(given, max_width);
+            assert_eq!(expected, actual, "Given: {} with {}", given, max_width);
+            assert!(display_width(&actual) <= max_width);
+        }
+
+        let path = "/home/u/Code/rust/deep/dir";
+        assert_case(path, 30, path);
+        assert_case(path, 26, path);
+        assert_case(path, 25, "/home/u/…/rust/deep/dir");
+        assert_case(path, 18, "/home/u/…/deep/dir");
+        assert_case(path, 16, "/home/…/deep/dir");
+        assert_case(path, 6, "/…/dir");
+        assert_case(path, 4, "…dir");
+        assert_case("/日本語/フォルダ/最後", 12, "/…/最後");
+        assert_case("no_separators", 5, "…tors");
+        assert_case(path, 0, "");
+    }
+}
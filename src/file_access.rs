@@ -1,16 +1,18 @@
 use log::info;
 
 use crate::paths::{self, DataFolderError};
-use crate::{AllFavorites, AppResult};
+use crate::{constants, AllFavorites, AppResult};
 use std::error::Error;
 use std::fmt::Display;
-use std::fs;
+use std::fs::{self, File, OpenOptions};
+use std::path::{Path, PathBuf};
 
 #[derive(Debug)]
 pub enum DataIoError {
     Io(std::io::Error),
     DataDir(DataFolderError),
     InvalidAppDataFormat(serde_json::Error),
+    DuplicateName(String),
 }
 
 impl Display for DataIoError {
@@ -21,36 +23,219 @@ impl Display for DataIoError {
             DataIoError::InvalidAppDataFormat(error) => {
                 writeln!(f, "Error in parsing app data: {}", error)
             }
+            DataIoError::DuplicateName(name) => writeln!(
+                f,
+                "Name {0} is used by several locations in the data file. \
+                Use \"rename {0} {0} --force\" to keep only the first of them \
+                or \"delete {0}\" to remove all of them",
+                name
+            ),
         }
     }
 }
 
 impl Error for DataIoError {}
 
-pub fn delete_everything() -> AppResult {
-    let to_load_from = paths::get_path_to_data().map_err(DataIoError::DataDir)?;
-    std::fs::remove_file(to_load_from)?;
+/// Exclusive lock on a file. Released when dropped.
+pub struct FileLock {
+    _file: File,
+}
+
+/// Waits until no other process of this app changes the data file.
+/// Has to be held from loading the favorites until saving them.
+pub fn lock_data_file() -> Result<FileLock, DataIoError> {
+    let data_file = paths::get_path_to_data().map_err(DataIoError::DataDir)?;
+    lock_data_file_at(&data_file)
+}
+
+/// Like [`lock_data_file`] for the given data file.
+pub fn lock_data_file_at(data_file: &Path) -> Result<FileLock, DataIoError> {
+    lock_file(&data_file.with_extension("lock")).map_err(DataIoError::Io)
+}
+
+/// Waits until the lock file is not locked by another process and locks it.
+/// The lock file is created if needed. Only locks on Unix.
+pub fn lock_file(lock_file: &Path) -> std::io::Result<FileLock> {
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(lock_file)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::io::AsRawFd;
+        // SAFETY: The file descriptor stays open as long as the file.
+        if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) } != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+    Ok(FileLock { _file: file })
+}
+
+pub fn delete_data_file(data_file: &Path) -> AppResult {
+    std::fs::remove_file(data_file)?;
     info!("All favorites paths were deleted.");
     Ok(())
 }
 
+/// Copies the data file into the folder "backups" next to it with the timestamp in its name
+/// like "favorites-1700000000.json". A counter is appended if a backup with the same timestamp
+/// exists. Only the newest [`constants::MAX_BACKUPS`] backups are kept.
+/// Returns none if there is no data file to back up.
+pub fn backup_data_file(data_file: &Path, timestamp: u64) -> Result<Option<PathBuf>, DataIoError> {
+    backup_file(data_file, timestamp, constants::MAX_BACKUPS)
+}
+
+fn backup_file(
+    data_file: &Path,
+    timestamp: u64,
+    keep: usize,
+) -> Result<Option<PathBuf>, DataIoError> {
+    if !data_file.exists() {
+        return Ok(None);
+    }
+
+    let backup_folder = data_file
+        .parent()
+        .map(|parent| parent.join(constants::BACKUP_FOLDER))
+        .unwrap_or_else(|| PathBuf::from(constants::BACKUP_FOLDER));
+    fs::create_dir_all(&backup_folder).map_err(DataIoError::Io)?;
+    let stem = data_file
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| constants::APP_NAME.to_string());
+    let mut backup = backup_folder.join(format!("{}-{}.json", stem, timestamp));
+    let mut counter = 1;
+    while backup.exists() {
+        backup = backup_folder.join(format!("{}-{}-{}.json", stem, timestamp, counter));
+        counter += 1;
+    }
+    fs::copy(data_file, &backup).map_err(DataIoError::Io)?;
+    info!("Saved backup of the data file at {:?}", backup);
+    remove_old_backups(&backup_folder, &stem, keep).map_err(DataIoError::Io)?;
+    Ok(Some(backup))
+}
+
+/// Other files in the backup folder are left alone.
+fn remove_old_backups(backup_folder: &Path, stem: &str, keep: usize) -> std::io::Result<()> {
+    let mut backups = Vec::new();
+    for entry in fs::read_dir(backup_folder)? {
+        let path = entry?.path();
+        if let Some(order) = backup_order(&path, stem) {
+            backups.push((order, path));
+        }
+    }
+    backups.sort();
+    let to_remove = backups.len().saturating_sub(keep);
+    for (_, old) in backups.into_iter().take(to_remove) {
+        info!("Removing old backup at {:?}", old);
+        fs::remove_file(old)?;
+    }
+    Ok(())
+}
+
+/// Timestamp and counter within a name like "favorites-1700000000-2.json".
+fn backup_order(path: &Path, stem: &str) -> Option<(u64, u64)> {
+    let name = path.file_name()?.to_str()?;
+    let numbers = name
+        .strip_prefix(stem)?
+        .strip_prefix('-')?
+        .strip_suffix(".json")?;
+    match numbers.split_once('-') {
+        Some((timestamp, counter)) => Some((timestamp.parse().ok()?, counter.parse().ok()?)),
+        None => Some((numbers.parse().ok()?, 0)),
+    }
+}
+
+/// Fails if a name is used by several favorites as only the first of them could be addressed.
 pub fn get_favorites() -> Result<AllFavorites, DataIoError> {
+    let favorites = get_favorites_for_repair()?;
+    if let Some(name) = favorites.find_duplicate_name() {
+        return Err(DataIoError::DuplicateName(name.to_string()));
+    }
+
+    Ok(favorites)
+}
+
+/// Loads the favorites even if a name is used by several of them.
+/// Only for commands which can remove such duplicates like delete and rename with force.
+pub fn get_favorites_for_repair() -> Result<AllFavorites, DataIoError> {
     let to_load_from = paths::get_path_to_data().map_err(DataIoError::DataDir)?;
+    read_favorites(&to_load_from)
+}
 
-    if !to_load_from.exists() {
+/// Like [`get_favorites_for_repair`] for the given data file.
+pub fn read_favorites(data_file: &Path) -> Result<AllFavorites, DataIoError> {
+    if !data_file.exists() {
         return Ok(AllFavorites::default());
     }
 
-    let raw_content = fs::read_to_string(&to_load_from).map_err(DataIoError::Io)?;
-    let favorites: AllFavorites =
-        serde_json::from_str(&raw_content).map_err(DataIoError::InvalidAppDataFormat)?;
-
-    Ok(favorites)
+    let raw_content = fs::read_to_string(data_file).map_err(DataIoError::Io)?;
+    serde_json::from_str(&raw_content).map_err(DataIoError::InvalidAppDataFormat)
 }
 
 pub fn save_favorites(to_save: AllFavorites) -> Result<(), DataIoError> {
     let to_save = serde_json::to_string(&to_save).map_err(DataIoError::InvalidAppDataFormat)?;
     let save_location = paths::get_path_to_data().map_err(DataIoError::DataDir)?;
 
-    fs::write(save_location, to_save).map_err(DataIoError::Io)
+    // Written into another file first so readers never see a partially written data file.
+    let mut temporary = save_location.clone().into_os_string();
+    temporary.push(format!(".{}.tmp", std::process::id()));
+    fs::write(&temporary, to_save).map_err(DataIoError::Io)?;
+    fs::rename(&temporary, save_location).map_err(DataIoError::Io)
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    use crate::test_utils::given_test_dir;
+
+    fn backup_names(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir.join(constants::BACKUP_FOLDER))
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn backup_with_counter_for_same_timestamp() {
+        let dir = given_test_dir("file_access_backup_counter");
+        let data_file = dir.join("favorites.json");
+        assert_eq!(None, backup_file(&data_file, 100, 10).unwrap());
+
+        fs::write(&data_file, "[]").unwrap();
+        for _ in 0..3 {
+            backup_file(&data_file, 100, 10).unwrap();
+        }
+        let last = backup_file(&data_file, 100, 10).unwrap().unwrap();
+        assert_eq!("[]", fs::read_to_string(last).unwrap());
+        assert_eq!(
+            vec![
+                "favorites-100-1.json",
+                "favorites-100-2.json",
+                "favorites-100-3.json",
+                "favorites-100.json"
+            ],
+            backup_names(&dir)
+        );
+    }
+
+    #[test]
+    fn keep_only_newest_backups() {
+        let dir = given_test_dir("file_access_backup_retention");
+        let data_file = dir.join("favorites.json");
+        fs::write(&data_file, "[]").unwrap();
+        fs::create_dir_all(dir.join(constants::BACKUP_FOLDER)).unwrap();
+        fs::write(dir.join(constants::BACKUP_FOLDER).join("notes.txt"), "").unwrap();
+
+        for timestamp in [9, 100, 100, 20] {
+            backup_file(&data_file, timestamp, 2).unwrap();
+        }
+        assert_eq!(
+            vec!["favorites-100-1.json", "favorites-100.json", "notes.txt"],
+            backup_names(&dir)
+        );
+    }
 }
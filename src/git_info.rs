@@ -0,0 +1,163 @@
+use std::fmt::Display;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// State of a git work tree containing a location.
+/// Only files below ".git" are read to find the head. No network is used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitInfo {
+    pub head: GitHead,
+    /// None if the command git could not tell.
+    pub dirty: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GitHead {
+    Branch(String),
+    /// Commit checked out without a branch. Contains the abbreviated hash.
+    Detached(String),
+}
+
+impl Display for GitHead {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GitHead::Branch(branch) => write!(f, "{}", branch),
+            GitHead::Detached(hash) => write!(f, "detached at {}", hash),
+        }
+    }
+}
+
+const SHORT_HASH_LEN: usize = 7;
+
+impl GitInfo {
+    /// None if the location is not within a git work tree.
+    pub fn detect(location: &Path) -> Option<Self> {
+        let (work_tree, git_dir) = find_git_dir(location)?;
+        let head = read_head(&git_dir)?;
+        Some(Self {
+            head,
+            dirty: is_dirty(&work_tree),
+        })
+    }
+
+    /// Looks at every location in its own thread so slow repositories do not add up.
+    pub fn detect_all(locations: &[PathBuf]) -> Vec<Option<Self>> {
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = locations
+                .iter()
+                .map(|location| scope.spawn(|| Self::detect(location)))
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap_or(None))
+                .collect()
+        })
+    }
+}
+
+/// Work tree and git folder of the nearest ".git" at or above the location.
+/// A ".git" file like in a linked work tree or a submodule points to the git folder.
+fn find_git_dir(location: &Path) -> Option<(PathBuf, PathBuf)> {
+    let start = if location.is_dir() {
+        location
+    } else {
+        location.parent()?
+    };
+    start.ancestors().find_map(|work_tree| {
+        let dot_git = work_tree.join(".git");
+        if dot_git.is_dir() {
+            return Some((work_tree.to_path_buf(), dot_git));
+        }
+        let content = fs::read_to_string(&dot_git).ok()?;
+        let git_dir = content.trim().strip_prefix("gitdir:")?.trim();
+        Some((work_tree.to_path_buf(), work_tree.join(git_dir)))
+    })
+}
+
+fn read_head(git_dir: &Path) -> Option<GitHead> {
+    let head = fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    let head = head.trim();
+    match head.strip_prefix("ref:") {
+        Some(reference) => {
+            let reference = reference.trim();
+            let branch = reference.strip_prefix("refs/heads/").unwrap_or(reference);
+            Some(GitHead::Branch(branch.to_string()))
+        }
+        None if !head.is_empty() && head.chars().all(|c| c.is_ascii_hexdigit()) => Some(
+            GitHead::Detached(head.chars().take(SHORT_HASH_LEN).collect()),
+        ),
+        None => None,
+    }
+}
+
+/// Asks the command git for uncommitted changes including untracked files.
+/// None if git is not installed or fails.
+fn is_dirty(work_tree: &Path) -> Option<bool> {
+    let output = Command::new("git")
+        .args(["--no-optional-locks", "status", "--porcelain"])
+        .current_dir(work_tree)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    output.status.success().then_some(!output.stdout.is_empty())
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    use crate::test_utils::given_test_dir;
+
+    #[test]
+    fn read_branch_and_detached_head() {
+        let dir = given_test_dir("git_info_head");
+        let repo = dir.join("repo");
+        fs::create_dir_all(repo.join(".git")).unwrap();
+        fs::create_dir_all(repo.join("src")).unwrap();
+        fs::write(repo.join("src/main.rs"), "").unwrap();
+        fs::write(repo.join(".git/HEAD"), "ref: refs/heads/feature/login\n").unwrap();
+
+        let (work_tree, git_dir) = find_git_dir(&repo.join("src/main.rs")).unwrap();
+        assert_eq!(repo, work_tree);
+        assert_eq!(
+            Some(GitHead::Branch("feature/login".to_string())),
+            read_head(&git_dir)
+        );
+
+        fs::write(
+            repo.join(".git/HEAD"),
+            "3f786850e387550fdab836ed7e6dc881de23001b\n",
+        )
+        .unwrap();
+        let actual = read_head(&git_dir).unwrap();
+        assert_eq!(GitHead::Detached("3f78685".to_string()), actual);
+        assert_eq!("detached at 3f78685", actual.to_string());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn follow_git_file_of_linked_work_tree() {
+        let dir = given_test_dir("git_info_linked");
+        let git_dir = dir.join("main/.git/worktrees/linked");
+        fs::create_dir_all(&git_dir).unwrap();
+        fs::write(git_dir.join("HEAD"), "ref: refs/heads/hotfix\n").unwrap();
+        let linked = dir.join("linked");
+        fs::create_dir_all(&linked).unwrap();
+        fs::write(
+            linked.join(".git"),
+            "gitdir: ../main/.git/worktrees/linked\n",
+        )
+        .unwrap();
+
+        let (work_tree, found) = find_git_dir(&linked).unwrap();
+        assert_eq!(linked, work_tree);
+        assert_eq!(
+            Some(GitHead::Branch("hotfix".to_string())),
+            read_head(&found)
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
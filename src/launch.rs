@@ -0,0 +1,359 @@
+use std::ffi::{OsStr, OsString};
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus};
+use std::str::FromStr;
+
+use thiserror::Error;
+
+/// Placeholder for the expanded location of a favorite.
+pub const PATH_PLACEHOLDER: &str = "{path}";
+/// Placeholder for the location if it is a folder otherwise the folder containing it.
+pub const DIR_PLACEHOLDER: &str = "{dir}";
+/// Placeholder for the name of a favorite.
+pub const NAME_PLACEHOLDER: &str = "{name}";
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum InvalidCommandLine {
+    #[error("Command line \"{0}\" contains an unterminated quote")]
+    UnterminatedQuote(String),
+    #[error("Command line is empty")]
+    Empty,
+}
+
+/// Program with its arguments which is started within a given folder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandLine {
+    program: OsString,
+    args: Vec<OsString>,
+    current_dir: PathBuf,
+}
+
+/// Values for the placeholders of a command line template.
+#[derive(Debug, Clone, Copy)]
+pub struct Placeholders<'a> {
+    pub name: &'a str,
+    pub path: &'a Path,
+    pub dir: &'a Path,
+}
+
+impl<'a> Placeholders<'a> {
+    pub fn new(name: &'a str, path: &'a Path) -> Self {
+        let dir = working_dir(path);
+        Self { name, path, dir }
+    }
+}
+
+/// The path itself for a folder otherwise the folder containing it.
+pub fn working_dir(path: &Path) -> &Path {
+    if path.is_dir() {
+        path
+    } else {
+        path.parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or(path)
+    }
+}
+
+impl CommandLine {
+    /// Splits the template into words like a shell and replaces the placeholders
+    /// `{path}`, `{dir}` and `{name}` within every word.
+    /// `to_append` is added as last word if the template contains no placeholder.
+    /// The command is started within `{dir}`.
+    pub fn from_template(
+        template: &str,
+        placeholders: Placeholders,
+        to_append: Option<&str>,
+    ) -> Result<Self, InvalidCommandLine> {
+        let mut words = split_words(template)?;
+        let has_placeholder = words.iter().any(|word| {
+            [PATH_PLACEHOLDER, DIR_PLACEHOLDER, NAME_PLACEHOLDER]
+                .iter()
+                .any(|placeholder| word.contains(placeholder))
+        });
+        if let (false, Some(to_append)) = (has_placeholder, to_append) {
+            words.push(to_append.to_string());
+        }
+
+        let mut words = words.iter().map(|word| substitute(word, placeholders));
+        let program = words.next().ok_or(InvalidCommandLine::Empty)?;
+        Ok(Self {
+            program,
+            args: words.collect(),
+            current_dir: placeholders.dir.to_path_buf(),
+        })
+    }
+
+    /// Program and arguments as given without any placeholders.
+    pub fn new(program: OsString, args: Vec<OsString>, current_dir: PathBuf) -> Self {
+        Self {
+            program,
+            args,
+            current_dir,
+        }
+    }
+
+    pub fn current_dir(&self) -> &Path {
+        &self.current_dir
+    }
+
+    pub fn to_command(&self) -> Command {
+        let mut command = Command::new(&self.program);
+        command.args(&self.args).current_dir(&self.current_dir);
+        command
+    }
+
+    /// Starts the program with the stdio of this app and waits for it to finish.
+    pub fn run(&self) -> std::io::Result<ExitStatus> {
+        self.to_command().status()
+    }
+}
+
+/// Exit code of a finished child process.
+/// A process killed by a signal results in 128 plus the number of the signal like in a shell.
+pub fn exit_code_of(status: ExitStatus) -> i32 {
+    if let Some(code) = status.code() {
+        return code;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+    1
+}
+
+/// Shows the command line in a form which can be pasted into a shell.
+impl Display for CommandLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let words: Vec<String> = std::iter::once(&self.program)
+            .chain(&self.args)
+            .map(|word| quote_for_shell(word))
+            .collect();
+        write!(f, "{}", words.join(" "))
+    }
+}
+
+/// Splits a command line into words like a shell without expanding anything.
+/// Single quotes keep everything literally. Within double quotes and outside of quotes
+/// a backslash keeps the next character literally.
+pub fn split_words(command_line: &str) -> Result<Vec<String>, InvalidCommandLine> {
+    let unterminated = || InvalidCommandLine::UnterminatedQuote(command_line.to_string());
+    let mut words = Vec::new();
+    let mut current: Option<String> = None;
+    let mut chars = command_line.chars();
+    while let Some(next) = chars.next() {
+        match next {
+            c if c.is_whitespace() => {
+                if let Some(word) = current.take() {
+                    words.push(word);
+                }
+            }
+            '\'' => {
+                let word = current.get_or_insert_with(String::new);
+                loop {
+                    match chars.next().ok_or_else(unterminated)? {
+                        '\'' => break,
+                        c => word.push(c),
+                    }
+                }
+            }
+            '"' => {
+                let word = current.get_or_insert_with(String::new);
+                loop {
+                    match chars.next().ok_or_else(unterminated)? {
+                        '"' => break,
+                        '\\' => word.push(chars.next().ok_or_else(unterminated)?),
+                        c => word.push(c),
+                    }
+                }
+            }
+            '\\' => {
+                let word = current.get_or_insert_with(String::new);
+                if let Some(c) = chars.next() {
+                    word.push(c);
+                }
+            }
+            c => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(current);
+    Ok(words)
+}
+
+/// Replaces every placeholder within the word without losing bytes which are not valid utf 8.
+fn substitute(word: &str, placeholders: Placeholders) -> OsString {
+    let values: [(&str, &OsStr); 3] = [
+        (PATH_PLACEHOLDER, placeholders.path.as_os_str()),
+        (DIR_PLACEHOLDER, placeholders.dir.as_os_str()),
+        (NAME_PLACEHOLDER, OsStr::new(placeholders.name)),
+    ];
+    let mut substituted = OsString::new();
+    let mut rest = word;
+    while !rest.is_empty() {
+        let next = values
+            .iter()
+            .filter_map(|(placeholder, value)| {
+                rest.find(placeholder)
+                    .map(|start| (start, placeholder, value))
+            })
+            .min_by_key(|(start, _, _)| *start);
+        match next {
+            Some((start, placeholder, value)) => {
+                substituted.push(&rest[..start]);
+                substituted.push(value);
+                rest = &rest[start + placeholder.len()..];
+            }
+            None => {
+                substituted.push(rest);
+                rest = "";
+            }
+        }
+    }
+    substituted
+}
+
+fn quote_for_shell(word: &OsStr) -> String {
+    let word = word.to_string_lossy();
+    let is_safe = |c: char| c.is_alphanumeric() || "-_./=:,+@%".contains(c);
+    if !word.is_empty() && word.chars().all(is_safe) {
+        word.into_owned()
+    } else {
+        format!("'{}'", word.replace('\'', r"'\''"))
+    }
+}
+
+/// With what the location of a favorite is opened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OpenWith {
+    /// App registered in the OS for the kind of the location.
+    System,
+    /// Editor given via the environment variable VISUAL or EDITOR.
+    Editor,
+    FileManager,
+    Terminal,
+    /// Command line with placeholders like "code --new-window {path}".
+    Custom(String),
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error(
+    "Unknown value \"{0}\". Expected \"system\", \"editor\", \"file-manager\" or \"terminal\". \
+    A command line is given via \"--with-command\""
+)]
+pub struct UnknownOpenWith(String);
+
+/// Only the keywords. A command line is never parsed from text
+/// so a typo is not started as program.
+impl FromStr for OpenWith {
+    type Err = UnknownOpenWith;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "system" => Ok(Self::System),
+            "editor" => Ok(Self::Editor),
+            "file-manager" => Ok(Self::FileManager),
+            "terminal" => Ok(Self::Terminal),
+            unknown => Err(UnknownOpenWith(unknown.to_string())),
+        }
+    }
+}
+
+impl OpenWith {
+    /// Placeholder appended to a template without any placeholder.
+    /// A terminal is only started within the folder.
+    pub fn to_append(&self) -> Option<&'static str> {
+        match self {
+            Self::FileManager => Some(DIR_PLACEHOLDER),
+            Self::Terminal => None,
+            Self::System | Self::Editor | Self::Custom(_) => Some(PATH_PLACEHOLDER),
+        }
+    }
+}
+
+/// Command for opening a location with the app registered in the OS.
+pub fn system_opener() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(windows) {
+        "explorer"
+    } else {
+        "xdg-open"
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn split_words_like_a_shell() {
+        fn assert_case(given: &str, expected: &[&str]) {
+            let actual = split_words(given).unwrap();
+            assert_eq!(expected, actual, "Given: {}", given);
+        }
+
+        assert_case("code  -w {path}", &["code", "-w", "{path}"]);
+        assert_case(
+            r#"'my editor' "a \"b\"" c\ d"#,
+            &["my editor", r#"a "b""#, "c d"],
+        );
+        assert_case("--title=''", &["--title="]);
+        assert_case("", &[]);
+        assert_eq!(
+            Err(InvalidCommandLine::UnterminatedQuote("vim 'x".to_string())),
+            split_words("vim 'x")
+        );
+    }
+
+    #[test]
+    fn parse_only_keywords_of_open_with() {
+        assert_eq!(Ok(OpenWith::FileManager), "file-manager".parse());
+        assert_eq!(
+            Err(UnknownOpenWith("edtor".to_string())),
+            "edtor".parse::<OpenWith>()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn exit_code_of_finished_command() {
+        fn assert_case(script: &str, expected: i32) {
+            let status = Command::new("sh").args(["-c", script]).status().unwrap();
+            assert_eq!(expected, exit_code_of(status), "Script: {}", script);
+        }
+
+        assert_case("exit 0", 0);
+        assert_case("exit 3", 3);
+        assert_case("kill -9 $$", 137);
+    }
+
+    #[test]
+    fn replace_placeholders_and_append_path() {
+        let placeholders = Placeholders {
+            name: "api",
+            path: Path::new("/home/some_user/api/Cargo.toml"),
+            dir: Path::new("/home/some_user/api"),
+        };
+
+        let actual =
+            CommandLine::from_template("code --title={name} {dir}", placeholders, None).unwrap();
+        assert_eq!("code --title=api /home/some_user/api", actual.to_string());
+        assert_eq!(Path::new("/home/some_user/api"), actual.current_dir());
+
+        let actual =
+            CommandLine::from_template("my\\ editor -w", placeholders, Some(PATH_PLACEHOLDER))
+                .unwrap();
+        assert_eq!(
+            "'my editor' -w /home/some_user/api/Cargo.toml",
+            actual.to_string()
+        );
+
+        assert_eq!(
+            Err(InvalidCommandLine::Empty),
+            CommandLine::from_template(" ", placeholders, None)
+        );
+    }
+}
@@ -1,6 +1,7 @@
 //! # Error handling strategy
 //!
 //! If an result is an error then it will be propagated up to the binary crate currently.
+//! Every error is converted into [`error::AppError`] which decides the exit code of the app.
 
 pub use all_favorites::AllFavorites;
 
@@ -8,17 +9,26 @@ pub mod all_favorites;
 pub mod app;
 pub mod cli_args;
 pub mod clipboard;
+pub mod config;
 pub mod constants;
 pub mod data_access;
+pub mod error;
 pub mod favorite_folder_record;
+pub mod favorite_location;
 pub mod file_access;
+pub mod git_info;
+pub mod launch;
+pub mod location_kind;
 pub mod logging;
+pub mod output_format;
 pub mod paths;
 pub mod prelude;
 
 mod trimmed_not_empty_text;
 
-use std::error::Error;
+#[cfg(test)]
+mod test_utils;
+
 pub mod favorite_table;
 
-pub type AppResult<T = ()> = Result<T, Box<dyn Error>>;
+pub type AppResult<T = ()> = Result<T, error::AppError>;
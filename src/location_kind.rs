@@ -0,0 +1,122 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use serde::Serialize;
+
+/// What is found at a location on the file system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LocationKind {
+    Dir,
+    File,
+    SymlinkToDir,
+    SymlinkToFile,
+    /// Symbolic link whose target does not exist.
+    BrokenSymlink,
+    /// Something else than a file or folder like a socket or a device.
+    Special,
+    Missing,
+    PermissionDenied,
+    /// Any other IO error while looking at the location.
+    IoError,
+}
+
+impl LocationKind {
+    /// Looks at the location without following a symbolic link first.
+    /// Then the target of a symbolic link is looked at.
+    pub fn classify(path: &Path) -> Self {
+        match fs::symlink_metadata(path) {
+            Ok(metadata) if metadata.file_type().is_symlink() => match fs::metadata(path) {
+                Ok(target) if target.is_dir() => Self::SymlinkToDir,
+                Ok(_) => Self::SymlinkToFile,
+                Err(error) if error.kind() == ErrorKind::NotFound => Self::BrokenSymlink,
+                Err(error) => Self::from_io_error(&error),
+            },
+            Ok(metadata) if metadata.is_dir() => match fs::read_dir(path) {
+                Ok(_) => Self::Dir,
+                Err(error) => Self::from_io_error(&error),
+            },
+            Ok(metadata) if metadata.is_file() => Self::File,
+            Ok(_) => Self::Special,
+            Err(error) => Self::from_io_error(&error),
+        }
+    }
+
+    fn from_io_error(error: &std::io::Error) -> Self {
+        match error.kind() {
+            ErrorKind::NotFound => Self::Missing,
+            ErrorKind::PermissionDenied => Self::PermissionDenied,
+            _ => Self::IoError,
+        }
+    }
+
+    /// True if something can be found at the location.
+    pub fn exists(&self) -> bool {
+        matches!(
+            self,
+            Self::Dir | Self::File | Self::SymlinkToDir | Self::SymlinkToFile | Self::Special
+        )
+    }
+
+    /// Short text shown in the kind column of the table.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Dir => "dir",
+            Self::File => "file",
+            Self::SymlinkToDir => "link→dir",
+            Self::SymlinkToFile => "link→file",
+            Self::BrokenSymlink => "broken link",
+            Self::Special => "special",
+            Self::Missing => "missing",
+            Self::PermissionDenied => "denied",
+            Self::IoError => "error",
+        }
+    }
+
+    /// Symbol shown in the icon column of the table.
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Self::Dir => "📁",
+            Self::File => "📄",
+            Self::SymlinkToDir | Self::SymlinkToFile => "🔗",
+            Self::BrokenSymlink => "💔",
+            Self::Special => "🔌",
+            Self::Missing => "❌",
+            Self::PermissionDenied => "🔒",
+            Self::IoError => "❗",
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod testing {
+    use super::*;
+    use crate::test_utils::given_test_dir;
+    use std::os::unix::fs::symlink;
+
+    #[test]
+    fn classify_files_folders_and_links() {
+        let dir = given_test_dir("location_kind_classify");
+        let file = dir.join("file.txt");
+        fs::write(&file, "content").unwrap();
+        symlink(&dir, dir.join("link_dir")).unwrap();
+        symlink(&file, dir.join("link_file")).unwrap();
+        symlink(dir.join("not_there"), dir.join("link_broken")).unwrap();
+
+        fn assert_case(given: &Path, expected: LocationKind) {
+            let actual = LocationKind::classify(given);
+            assert_eq!(expected, actual, "Given: {:?}", given);
+        }
+
+        assert_case(&dir, LocationKind::Dir);
+        assert_case(&file, LocationKind::File);
+        assert_case(&dir.join("link_dir"), LocationKind::SymlinkToDir);
+        assert_case(&dir.join("link_file"), LocationKind::SymlinkToFile);
+        assert_case(&dir.join("link_broken"), LocationKind::BrokenSymlink);
+        assert_case(&dir.join("not_there"), LocationKind::Missing);
+        assert_case(&file.join("below_file"), LocationKind::IoError);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
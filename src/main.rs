@@ -1,8 +1,15 @@
 use clap::Parser;
-use folder_favorite::{cli_args::CliCommands, clipboard, data_access, AppResult};
+use folder_favorite::app::theme::Theme;
+use folder_favorite::app::{console_interaction, term_colors};
+use folder_favorite::cli_args::{CliArgs, CliCommands, ConfigCommands};
+use folder_favorite::clipboard::{self, ClipboardSettings};
+use folder_favorite::config::AppConfig;
+use folder_favorite::favorite_folder_record::LocationOptions;
+use folder_favorite::{config, data_access, paths, AppResult};
 
 use folder_favorite::{app, logging};
-use log::debug;
+use log::{debug, warn};
+use std::path::Path;
 
 fn main() {
     logging::set_up_logging();
@@ -12,34 +19,178 @@ fn main() {
     // Need to check if the program was started as child process to keep set clipboard alive
     // for Linux
     if let Err(error) = clipboard::execute_as_possible_daemon_clipboard() {
-        app::exit_with_error(&*error);
+        app::exit_with_error(&error.into());
     }
 
-    let args = CliCommands::parse();
-    if let Err(error) = handle_subcommand(args) {
-        app::exit_with_error(&*error);
+    let args = CliArgs::parse();
+    app::use_error_format(args.error_format);
+    data_access::use_dry_run(args.dry_run);
+    let config_path = match args
+        .config
+        .clone()
+        .map_or_else(paths::get_path_to_config, Ok)
+    {
+        Ok(config_path) => config_path,
+        Err(error) => app::exit_with_error(&error.into()),
+    };
+    let config = match config::load(&config_path) {
+        Ok(config) => config,
+        // An invalid config file can still be fixed via the subcommand config
+        // and is reported by the subcommand doctor.
+        Err(error) if matches!(args.command, CliCommands::Config(_) | CliCommands::Doctor) => {
+            warn!("{}", error);
+            AppConfig::default()
+        }
+        Err(error) => app::exit_with_error(&error.into()),
+    };
+
+    let colors = args
+        .color
+        .or(config.color())
+        .unwrap_or_default()
+        .should_colorize();
+    colored::control::set_override(colors);
+    term_colors::use_theme(Theme::from_config(args.theme, config.theme()));
+    if let Some(data_file) = config.data_file() {
+        paths::use_data_file(data_file);
+    }
+    data_access::use_usage_tracking(config.track_usage());
+    clipboard::use_settings(ClipboardSettings {
+        backend: args
+            .clipboard_backend
+            .or(config.clipboard().backend)
+            .unwrap_or_default(),
+        command: config.clipboard().command.clone(),
+        paste_command: config.clipboard().paste_command.clone(),
+    });
+    if let Err(error) = handle_subcommand(args.command, &config, &config_path, colors, args.dry_run)
+    {
+        app::exit_with_error(&error);
     }
 }
 
-fn handle_subcommand(sub_commands: CliCommands) -> AppResult {
+fn handle_subcommand(
+    sub_commands: CliCommands,
+    config: &AppConfig,
+    config_path: &Path,
+    colors: bool,
+    dry_run: bool,
+) -> AppResult {
     debug!("Handling subcommand {:?}", sub_commands);
     match sub_commands {
         CliCommands::Set {
             name_favorite,
             new_path,
-        } => data_access::set_favorite_data(name_favorite, new_path),
-        CliCommands::Get(get_params) => {
-            let output = app::handle_get_subcommand(&get_params)?;
-            app::put_into_clipboard_or_print(&output, get_params.copy_has_clipboard())
+            from_clipboard: _,
+            force,
+            canonicalize,
+            must_exist,
+            overwrite,
+        } => {
+            let options = LocationOptions {
+                canonicalize,
+                must_exist,
+            };
+            // Clap ensures that either a path or "--from-clipboard" is given.
+            match new_path {
+                Some(new_path) => data_access::set_favorite_data(
+                    name_favorite,
+                    new_path,
+                    options,
+                    overwrite.overwrite(),
+                    console_interaction::confirm_if_terminal,
+                ),
+                None => data_access::set_from_clipboard(
+                    name_favorite,
+                    options,
+                    force,
+                    overwrite.overwrite(),
+                    console_interaction::confirm_if_terminal,
+                ),
+            }
+        }
+        CliCommands::Get(mut get_params) => {
+            get_params.apply_config(config);
+            let output = app::handle_get_subcommand(&get_params, colors)?;
+            let clipboard = get_params
+                .copy_has_clipboard()
+                .then(|| get_params.clipboard_options());
+            app::put_into_clipboard_or_print(&output, clipboard)
         }
         CliCommands::Delete { name_favorite } => data_access::remove_from_fav(&name_favorite),
         CliCommands::Rename {
             old_name_favorite,
             new_name_favorite,
-        } => data_access::rename_fav(&old_name_favorite, new_name_favorite),
-        CliCommands::Reset => data_access::reset(),
-        CliCommands::PwdSet { name_favorite } => data_access::set_label_to_cwd(name_favorite),
-        CliCommands::Clean => data_access::remove_all_non_existing(),
+            force,
+        } => data_access::rename_fav(&old_name_favorite, new_name_favorite, force),
+        CliCommands::Reset { yes } => {
+            data_access::reset(yes, console_interaction::confirm_if_terminal)
+        }
+        CliCommands::PwdSet {
+            name_favorite,
+            overwrite,
+        } => data_access::set_label_to_cwd(
+            name_favorite,
+            overwrite.overwrite(),
+            console_interaction::confirm_if_terminal,
+        ),
+        CliCommands::Tag {
+            name_favorite,
+            tags,
+            remove,
+        } => data_access::tag_fav(&name_favorite, &tags, remove),
+        CliCommands::ClipSet {
+            name_favorite,
+            force,
+            overwrite,
+        } => data_access::set_from_clipboard(
+            name_favorite,
+            LocationOptions::default(),
+            force,
+            overwrite.overwrite(),
+            console_interaction::confirm_if_terminal,
+        ),
+        CliCommands::Clean { yes } => {
+            data_access::remove_all_non_existing(yes, console_interaction::confirm_if_terminal)
+        }
+        CliCommands::Open(open_params) => {
+            app::open::handle_open_subcommand(&open_params, config.open())
+        }
+        CliCommands::Exec(exec_params) => {
+            let exit_code = app::exec::handle_exec_subcommand(&exec_params)?;
+            std::process::exit(exit_code)
+        }
+        CliCommands::Foreach(foreach_params) => {
+            let exit_code = app::foreach::handle_foreach_subcommand(&foreach_params)?;
+            std::process::exit(exit_code)
+        }
+        CliCommands::Config(config_command) => {
+            handle_config_subcommand(config_command, config_path, dry_run)
+        }
+        CliCommands::Doctor => app::doctor::handle_doctor_subcommand(config_path),
     }?;
     Ok(())
 }
+
+fn handle_config_subcommand(
+    config_command: ConfigCommands,
+    config_path: &Path,
+    dry_run: bool,
+) -> AppResult {
+    match config_command {
+        ConfigCommands::Get { key } => println!("{}", config::get_value(config_path, &key)?),
+        ConfigCommands::Set { key, value } if dry_run => {
+            println!("{}", config::describe_set_value(config_path, &key, &value)?)
+        }
+        ConfigCommands::Set { key, value } => config::set_value(config_path, &key, &value)?,
+        ConfigCommands::List => {
+            for line in config::list_values(config_path)? {
+                println!("{}", line);
+            }
+        }
+        ConfigCommands::Path => {
+            app::print_os_str(config_path.as_os_str())?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use std::path::PathBuf;
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::favorite_folder_record::FavoriteFolderPath;
+use crate::favorite_location::{serialize_os_str, FavoriteLocation};
+use crate::location_kind::LocationKind;
+use crate::trimmed_not_empty_text::NonEmptyText;
+
+/// Format in which the subcommand get outputs favorites.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputFormat {
+    /// Plain location for a name or a table for a listing.
+    #[default]
+    Table,
+    /// JSON object for a name or a JSON array for a listing.
+    Json,
+}
+
+/// Entry of one favorite in the JSON output.
+#[derive(Debug, Serialize)]
+pub struct FavoriteReport<'a> {
+    name: &'a str,
+    /// Location as saved.
+    location: &'a FavoriteLocation,
+    /// Location with expanded "~" and environment variables or joined with a relative path.
+    #[serde(serialize_with = "serialize_os_str")]
+    path: PathBuf,
+    kind: LocationKind,
+    exists: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    created: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_used: Option<u64>,
+    use_count: u64,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    tags: &'a [NonEmptyText],
+}
+
+impl<'a> FavoriteReport<'a> {
+    pub fn new(favorite: &'a FavoriteFolderPath) -> Self {
+        Self::with_path(favorite, favorite.path())
+    }
+
+    /// Report for a favorite where a relative path was joined onto its location.
+    pub fn with_path(favorite: &'a FavoriteFolderPath, path: PathBuf) -> Self {
+        let kind = LocationKind::classify(&path);
+        Self {
+            name: favorite.get_name(),
+            location: favorite.location(),
+            path,
+            kind,
+            exists: kind.exists(),
+            created: favorite.created(),
+            last_used: favorite.last_used(),
+            use_count: favorite.use_count(),
+            tags: favorite.tags(),
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+pub fn favorites_to_json(favorites: &[FavoriteFolderPath]) -> serde_json::Result<String> {
+    let reports: Vec<FavoriteReport> = favorites.iter().map(FavoriteReport::new).collect();
+    serde_json::to_string_pretty(&reports)
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn report_kind_and_usage_as_json() {
+        let mut given = FavoriteFolderPath::new(
+            NonEmptyText::unwrap("not_there"),
+            FavoriteLocation::unwrap("${FAV_FOLDER_NOT_SET:-/fav_folder_not_there}/at_all"),
+        )
+        .with_created(10);
+        given.mark_as_used(20);
+
+        let actual = favorites_to_json(&[given]).unwrap();
+        insta::assert_snapshot!(actual);
+    }
+}
@@ -1,5 +1,7 @@
 use crate::prelude::*;
+use std::borrow::Cow;
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 use thiserror::Error;
 
 pub const fn is_in_debug() -> bool {
@@ -12,15 +14,82 @@ pub enum DataFolderError {
     CouldNotLocate,
     #[error("Could not make sure there is a data folder at ({0:?}) for this application")]
     CouldNotEnsure(PathBuf),
+    #[error("Could not find a location where a config folder is located in general")]
+    CouldNotLocateConfig,
+}
+
+static DATA_FILE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Uses the given data file instead of the one at the typical app data location of the OS.
+/// Only the first call has an effect.
+pub fn use_data_file(data_file: PathBuf) {
+    let _ = DATA_FILE.set(data_file);
 }
 
 pub fn get_path_to_data() -> Result<PathBuf, DataFolderError> {
+    if let Some(data_file) = DATA_FILE.get() {
+        if let Some(parent) = data_file.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|_| DataFolderError::CouldNotEnsure(parent.to_path_buf()))?;
+        }
+        debug!("Path to application data from config {:?}", data_file);
+        return Ok(data_file.clone());
+    }
     let mut data_dir = get_data_dir()?;
     data_dir.push(constants::APP_DATA_FILE);
     debug!("Path to application data {:?}", data_dir);
     Ok(data_dir)
 }
 
+/// The config file does not need to exist.
+pub fn get_path_to_config() -> Result<PathBuf, DataFolderError> {
+    let config_folder = if is_in_debug() {
+        get_tmp_folder_root()
+    } else {
+        dirs::config_dir().ok_or(DataFolderError::CouldNotLocateConfig)?
+    };
+    let config_file = config_folder
+        .join(constants::APP_NAME)
+        .join(constants::APP_CONFIG_FILE);
+    debug!("Path to config file {:?}", config_file);
+    Ok(config_file)
+}
+
+/// Contains the pid of the running clipboard daemon. Located in the data folder of this app.
+pub fn get_path_to_clipboard_pid() -> Result<PathBuf, DataFolderError> {
+    Ok(get_data_dir()?.join(constants::CLIPBOARD_PID_FILE))
+}
+
+/// Expands a leading `~` to the home directory and environment variables like `$VAR`, `${VAR}`
+/// and `${VAR:-default}`.
+/// If a variable is not set and has no default value then the location is returned unchanged.
+pub fn expand_location<'a>(
+    location: &'a str,
+    home_dir: impl FnOnce() -> Option<String>,
+    get_env: impl FnMut(&str) -> Option<String>,
+) -> Cow<'a, str> {
+    shellexpand::full_with_context_no_errors(location, home_dir, get_env)
+}
+
+pub(crate) fn expand_location_from_env(location: &str) -> Cow<'_, str> {
+    expand_location(
+        location,
+        || dirs::home_dir().and_then(|home| home.to_str().map(String::from)),
+        |var| std::env::var(var).ok(),
+    )
+}
+
+/// Name of the first variable in the location which is not set and has no default value.
+/// Such a variable is left unexpanded by [`expand_location`].
+pub fn find_unset_var(
+    location: &str,
+    mut get_env: impl FnMut(&str) -> Option<String>,
+) -> Option<String> {
+    shellexpand::env_with_context(location, |var| get_env(var).map(Some).ok_or(()))
+        .err()
+        .map(|error| error.var_name)
+}
+
 fn locate_data_folder_of_user() -> Result<PathBuf, DataFolderError> {
     dirs::data_dir().ok_or(DataFolderError::CouldNotLocate)
 }
@@ -43,3 +112,48 @@ fn get_data_dir() -> Result<PathBuf, DataFolderError> {
         .map_err(|_| DataFolderError::CouldNotEnsure(data_folder_for_this_app.clone()))?;
     Ok(data_folder_for_this_app)
 }
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn expand_tilde_and_env_vars() {
+        fn assert_case(given: &str, expected: &str) {
+            let actual = expand_location(
+                given,
+                || Some("/home/some_user".to_string()),
+                |var| match var {
+                    "CODE" => Some("/home/some_user/Code".to_string()),
+                    _ => None,
+                },
+            );
+            assert_eq!(expected, actual, "Given: {}", given);
+        }
+
+        assert_case("/home/some_user/Code", "/home/some_user/Code");
+        assert_case("~", "/home/some_user");
+        assert_case("~/Code/rust", "/home/some_user/Code/rust");
+        assert_case("$CODE/rust", "/home/some_user/Code/rust");
+        assert_case("${CODE}/rust", "/home/some_user/Code/rust");
+        assert_case("${NOT_SET:-/tmp}/rust", "/tmp/rust");
+        assert_case("${CODE:-/tmp}/rust", "/home/some_user/Code/rust");
+        assert_case("$NOT_SET/rust", "$NOT_SET/rust");
+    }
+
+    #[test]
+    fn find_variables_without_value() {
+        fn assert_case(given: &str, expected: Option<&str>) {
+            let actual = find_unset_var(given, |var| {
+                (var == "CODE").then(|| "/home/some_user/Code".to_string())
+            });
+            assert_eq!(expected, actual.as_deref(), "Given: {}", given);
+        }
+
+        assert_case("~/Code", None);
+        assert_case("$CODE/rust", None);
+        assert_case("${NOT_SET:-/tmp}/rust", None);
+        assert_case("$CODE/$NOT_SET/rust", Some("NOT_SET"));
+        assert_case("${NOT_SET}/rust", Some("NOT_SET"));
+    }
+}
@@ -0,0 +1,15 @@
+//! Helpers shared by the unit tests of several modules.
+
+use std::fs;
+use std::path::PathBuf;
+
+/// Empty folder within the temporary folder of the OS which is unique for this test run.
+/// `name` has to be unique among all tests like "<module>_<test>".
+pub fn given_test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir()
+        .join(crate::constants::TMP_PREFIX)
+        .join(format!("{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
@@ -0,0 +1,50 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+const APP: &str = env!("CARGO_BIN_EXE_folder_favorite");
+
+/// Config file within an empty folder which points to its own data file in that folder.
+fn given_config(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir()
+        .join("local_development_data_folder")
+        .join(format!("cli_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let config = dir.join("config.toml");
+    let data_file = dir.join("favorites.json");
+    fs::write(
+        &config,
+        format!("data_file = {:?}\n", data_file.to_str().unwrap()),
+    )
+    .unwrap();
+    config
+}
+
+fn run(config: &PathBuf, args: &[&std::ffi::OsStr]) -> Output {
+    Command::new(APP)
+        .arg("--config")
+        .arg(config)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[cfg(unix)]
+#[test]
+fn set_location_which_is_not_valid_utf8() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let config = given_config("non_utf8");
+    let location = OsStr::from_bytes(b"/tmp/\xff");
+
+    let output = run(&config, &["set".as_ref(), "bad".as_ref(), location]);
+    assert!(output.status.success(), "{:?}", output);
+
+    let output = run(&config, &["get".as_ref(), "bad".as_ref()]);
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(b"/tmp/\xff\n".as_slice(), output.stdout);
+
+    fs::remove_dir_all(config.parent().unwrap()).unwrap();
+}